@@ -2,35 +2,49 @@
 // SPDX-License-Identifier: gpl-3.0-only
 
 use lib_gbemu::{
-    gpu::{Color as GbColor, GbWindow, X_RES, Y_RES},
+    gpu::{
+        events::{EventFilter, EventKind, PpuEvent},
+        filter::PostProcess,
+        inspector::{self, Image},
+        ppu::{LINES_PER_FRAME, TICKS_PER_LINE},
+        scaler::Scaler,
+        Color as GbColor, GbWindow, X_RES, Y_RES,
+    },
     memory::Bus,
 };
 
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    mpsc::{channel, Sender},
-    Arc, Mutex,
-};
-
-use sdl2::{pixels::Color, rect::Rect, render::Canvas, video::Window};
+use sdl2::{pixels::Color, render::Canvas, video::Window};
 
+use font::{draw_text, text_width};
 use utils::ToColor;
 
-use crate::SCALE;
-
-const DEBUG: bool = false;
-
-pub const TILE_COLORS: [Color; 4] = [
-    Color::RGB(255, 255, 255),
-    Color::RGB(175, 175, 175),
-    Color::RGB(85, 85, 85),
-    Color::RGB(0, 0, 0),
+const DBG_SCALE: usize = 2;
+const DBG_MARGIN: usize = 8;
+const DBG_CONTENT_Y: usize = 24;
+const DBG_BACKGROUND: GbColor = 0xFF111111;
+const TEXT_COLOR: GbColor = 0xFFFFFFFF;
+const DIM_TEXT_COLOR: GbColor = 0xFF808080;
+const VIEWPORT_COLOR: GbColor = 0xFFFF0000;
+const OAM_ROWS: usize = 20;
+
+// timeline grid: one pixel per dot, TIMELINE_ROW pixels per line
+const TIMELINE_ROW: usize = 3;
+const TIMELINE_HOVER_DOTS: u16 = 8;
+const TIMELINE_HOVER_LINES: usize = 12;
+const NO_MODE_COLOR: GbColor = 0xFF000000;
+const MODE_COLORS: [GbColor; 4] = [0xFF202850, 0xFF303030, 0xFF284828, 0xFF603020];
+const MODE_NAMES: [&str; 4] = ["HBLANK", "VBLANK", "OAM", "XFER"];
+const INTERRUPT_NAMES: [&str; 5] = ["VBLANK", "STAT", "TIMER", "SERIAL", "JOYPAD"];
+const INTERRUPT_COLOR: GbColor = 0xFFFFE040;
+const IO_WRITE_COLOR: GbColor = 0xFF40E0FF;
+const DMA_COLOR: GbColor = 0xFFFF40FF;
+const EVENT_FILTERS: [(EventFilter, &str, GbColor); 4] = [
+    (EventFilter::Mode, "1 MODE", 0xFF80C080),
+    (EventFilter::Interrupt, "2 INT", INTERRUPT_COLOR),
+    (EventFilter::IoWrite, "3 IO", IO_WRITE_COLOR),
+    (EventFilter::DmaStart, "4 DMA", DMA_COLOR),
 ];
 
-fn delay(milis: u64) {
-    std::thread::sleep(std::time::Duration::from_millis(milis));
-}
-
 fn get_ticks() -> u64 {
     let now = std::time::SystemTime::now();
     now.duration_since(std::time::UNIX_EPOCH)
@@ -38,14 +52,31 @@ fn get_ticks() -> u64 {
         .as_millis() as u64
 }
 
-type SharedCounter = Arc<AtomicUsize>;
-
-struct FrameTask {
-    start_y: usize,
-    end_y: usize,
-    buffer: Arc<[GbColor]>,
-    pixel_data: Arc<Mutex<Vec<u8>>>,
-    done_counter: SharedCounter,
+/// Stretches a `width` x `height` frame over the whole canvas
+fn copy_to_canvas(canvas: &mut Canvas<Window>, buffer: &[GbColor], width: usize, height: usize) {
+    let texture_creator = canvas.texture_creator();
+    let mut texture = texture_creator
+        .create_texture_streaming(
+            sdl2::pixels::PixelFormatEnum::RGB24,
+            width as u32,
+            height as u32,
+        )
+        .unwrap();
+
+    texture
+        .with_lock(None, |pixels, pitch| {
+            for (y, row) in buffer.chunks(width).enumerate() {
+                let line = &mut pixels[y * pitch..y * pitch + width * 3];
+                for (pixel, color) in line.chunks_mut(3).zip(row) {
+                    let color = color.to_color();
+                    pixel[0] = color.r;
+                    pixel[1] = color.g;
+                    pixel[2] = color.b;
+                }
+            }
+        })
+        .unwrap();
+    canvas.copy(&texture, None, None).unwrap();
 }
 
 pub struct MainWindow {
@@ -54,58 +85,22 @@ pub struct MainWindow {
     pub prev_frame_time: u64,
     pub start_time: u64,
     pub frame_count: u64,
-    thread_senders: Vec<Sender<FrameTask>>,
+    pub post_process: PostProcess,
+    pub scaler: Scaler,
+    scaled: Vec<GbColor>,
 }
 
 impl MainWindow {
     pub fn new(canvas: Canvas<Window>) -> Self {
-        const NUM_THREADS: usize = 4;
-
-        let mut thread_senders = Vec::new();
-
-        for _ in 0..NUM_THREADS {
-            let (tx, rx) = channel::<FrameTask>();
-            thread_senders.push(tx);
-
-            std::thread::spawn(move || {
-                while let Ok(task) = rx.recv() {
-                    let FrameTask {
-                        start_y,
-                        end_y,
-                        buffer,
-                        pixel_data,
-                        done_counter,
-                    } = task;
-
-                    let mut local = vec![0u8; X_RES as usize * (end_y - start_y) * 3];
-                    for y in start_y..end_y {
-                        for x in 0..X_RES as usize {
-                            let index = x + y * X_RES as usize;
-                            let color = buffer[index].to_color();
-                            let pixel_index = (y - start_y) * X_RES as usize * 3 + x * 3;
-                            local[pixel_index] = color.r;
-                            local[pixel_index + 1] = color.g;
-                            local[pixel_index + 2] = color.b;
-                        }
-                    }
-
-                    {
-                        let mut pd = pixel_data.lock().unwrap();
-                        let offset = start_y * X_RES as usize * 3;
-                        pd[offset..offset + local.len()].copy_from_slice(&local);
-                    }
-
-                    done_counter.fetch_add(1, Ordering::SeqCst);
-                }
-            });
-        }
         Self {
             canvas,
             target_frame_time: 0,
             prev_frame_time: 0,
             frame_count: 0,
             start_time: 0,
-            thread_senders,
+            post_process: PostProcess::new(),
+            scaler: Scaler::default(),
+            scaled: Vec::new(),
         }
     }
 
@@ -138,48 +133,17 @@ impl GbWindow for MainWindow {
             self.frame_count = 0;
         }
 
-        let texture_creator = self.canvas.texture_creator();
-        let mut texture = texture_creator
-            .create_texture_streaming(
-                sdl2::pixels::PixelFormatEnum::RGB24,
-                X_RES as u32,
-                Y_RES as u32,
-            )
-            .unwrap();
-
-        let shared_pixel_data = Arc::new(Mutex::new(vec![0u8; (X_RES * Y_RES * 3) as usize]));
-        let shared_buffer: Arc<[GbColor]> = Arc::from(buffer.to_vec().into_boxed_slice());
-        let done_counter = Arc::new(AtomicUsize::new(0));
-        let num_threads = self.thread_senders.len();
-        let chunk_height = Y_RES as usize / num_threads;
-
-        for (i, tx) in self.thread_senders.iter().enumerate() {
-            let start_y = i * chunk_height;
-            let end_y = if i == num_threads - 1 {
-                Y_RES as usize
-            } else {
-                (i + 1) * chunk_height
-            };
-
-            tx.send(FrameTask {
-                start_y,
-                end_y,
-                buffer: shared_buffer.clone(),
-                pixel_data: shared_pixel_data.clone(),
-                done_counter: done_counter.clone(),
-            })
-            .unwrap();
-        }
-
-        while done_counter.load(Ordering::SeqCst) < num_threads {
-            std::thread::yield_now();
-        }
-
-        let pixel_data = shared_pixel_data.lock().unwrap();
-        texture
-            .update(None, &pixel_data, (X_RES * 3) as usize)
-            .unwrap();
-        self.canvas.copy(&texture, None, None).unwrap();
+        // the dot-matrix filter already outputs at window scale
+        let scaled_by_filter = self.post_process.dot_matrix;
+        let (buffer, width, height) = self.post_process.apply(buffer);
+        let (buffer, width, height) = if scaled_by_filter {
+            (buffer, width, height)
+        } else {
+            let (width, height) = self.scaler.apply(buffer, width, height, &mut self.scaled);
+            (&self.scaled[..], width, height)
+        };
+
+        copy_to_canvas(&mut self.canvas, buffer, width, height);
         self.canvas.present();
 
         self.frame_count += 1;
@@ -192,7 +156,44 @@ impl GbWindow for MainWindow {
     }
 }
 
-pub struct DebugWindow(pub Canvas<Window>);
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DebugPage {
+    #[default]
+    Tiles,
+    BgMap0,
+    BgMap1,
+    WindowMap,
+    Oam,
+    Palettes,
+    Timeline,
+}
+
+impl DebugPage {
+    pub const ALL: [DebugPage; 7] = [
+        DebugPage::Tiles,
+        DebugPage::BgMap0,
+        DebugPage::BgMap1,
+        DebugPage::WindowMap,
+        DebugPage::Oam,
+        DebugPage::Palettes,
+        DebugPage::Timeline,
+    ];
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|page| *page == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+pub struct DebugWindow {
+    pub canvas: Canvas<Window>,
+    pub page: DebugPage,
+    /// Mouse position inside the debug window
+    pub mouse: Option<(i32, i32)>,
+    /// EventFilter bits shown on the timeline
+    pub event_filter: u8,
+    image: Image,
+}
 
 pub struct DebugMode {
     pub main_window: MainWindow,
@@ -201,89 +202,415 @@ pub struct DebugMode {
 }
 
 impl DebugWindow {
-    #[inline(always)]
-    fn display_tile(
-        bus: &Bus,
-        canvas: &mut Canvas<Window>,
-        address: u16,
-        tile_num: u16,
-        x: i32,
-        y: i32,
-    ) {
-        for tile_y in (0..16).step_by(2) {
-            let byte1: u8 = bus.read(address + (tile_num * 16) + tile_y);
-            let byte2: u8 = bus.read(address + (tile_num * 16) + tile_y + 1);
-
-            if DEBUG {
-                println!(
-                    "Tile {} Byte1: {:08b}, Byte2: {:08b}",
-                    tile_num, byte1, byte2
-                );
+    pub fn new(canvas: Canvas<Window>) -> Self {
+        let (width, height) = canvas.output_size().unwrap();
+
+        Self {
+            canvas,
+            page: DebugPage::default(),
+            mouse: None,
+            event_filter: EventFilter::ALL,
+            image: Image::new(width as usize, height as usize),
+        }
+    }
+
+    fn draw_tiles(&mut self, bus: &Bus) {
+        let tiles = inspector::tile_data(&bus.ppu);
+        let tile_size = 8 * DBG_SCALE;
+        let x = DBG_MARGIN + tiles.width * DBG_SCALE + DBG_MARGIN;
+
+        self.image
+            .blit(&tiles, DBG_MARGIN, DBG_CONTENT_Y, DBG_SCALE);
+        for (block, address) in [0x8000, 0x8800, 0x9000].iter().enumerate() {
+            let y = DBG_CONTENT_Y + block * 8 * tile_size;
+            draw_text(
+                &mut self.image,
+                &format!("{:04X}", address),
+                x,
+                y,
+                DBG_SCALE,
+                TEXT_COLOR,
+            );
+        }
+    }
+
+    fn draw_map(&mut self, bus: &Bus, map_area: u16) {
+        let lcd = &bus.ppu.lcd;
+        let mut map = inspector::tile_map(&bus.ppu, map_area);
+
+        if lcd.bg_map_area() == map_area {
+            map.wrapped_rect(
+                lcd.scroll_x as usize,
+                lcd.scroll_y as usize,
+                X_RES as usize,
+                Y_RES as usize,
+                VIEWPORT_COLOR,
+            );
+        }
+
+        self.image.blit(&map, DBG_MARGIN, DBG_CONTENT_Y, DBG_SCALE);
+    }
+
+    fn draw_window_map(&mut self, bus: &Bus) {
+        let lcd = &bus.ppu.lcd;
+        let mut map = inspector::tile_map(&bus.ppu, lcd.win_map_area());
+
+        // part of the window map that fits on screen at the current WX/WY
+        let left = lcd.win_x.saturating_sub(7) as usize;
+        if lcd.is_window_enabled() != 0 && left < X_RES as usize && lcd.win_y < Y_RES as u8 {
+            map.wrapped_rect(
+                0,
+                0,
+                X_RES as usize - left,
+                Y_RES as usize - lcd.win_y as usize,
+                VIEWPORT_COLOR,
+            );
+        }
+
+        self.image.blit(&map, DBG_MARGIN, DBG_CONTENT_Y, DBG_SCALE);
+    }
+
+    fn draw_oam(&mut self, bus: &Bus) {
+        let row_height = 16 * DBG_SCALE + 2;
+        let column_width = (self.image.width - DBG_MARGIN) / 2;
+
+        for (index, oam) in bus.ppu.oam_ram.iter().enumerate() {
+            let x = DBG_MARGIN + (index / OAM_ROWS) * column_width;
+            let y = DBG_CONTENT_Y + (index % OAM_ROWS) * row_height;
+            let on_screen = oam.x() > 0 && oam.x() < 168 && oam.y() > 0 && oam.y() < 160;
+
+            let sprite = inspector::sprite(&bus.ppu, index);
+            self.image.blit(&sprite, x, y, DBG_SCALE);
+
+            let text = format!(
+                "{:02} X{:3} Y{:3} T{:02X} {}{}{}{}",
+                index,
+                oam.x(),
+                oam.y(),
+                oam.tile(),
+                if oam.f_pn() { '1' } else { '0' },
+                if oam.f_x_flip() { 'X' } else { '-' },
+                if oam.f_y_flip() { 'Y' } else { '-' },
+                if oam.f_bgp() { 'B' } else { '-' },
+            );
+            let color = if on_screen {
+                TEXT_COLOR
+            } else {
+                DIM_TEXT_COLOR
+            };
+            draw_text(
+                &mut self.image,
+                &text,
+                x + 8 * DBG_SCALE + DBG_MARGIN,
+                y,
+                DBG_SCALE,
+                color,
+            );
+        }
+    }
+
+    fn draw_palettes(&mut self, bus: &Bus) {
+        let lcd = &bus.ppu.lcd;
+        let swatch = 16 * DBG_SCALE;
+        let rows = [
+            ("BGP ", Some(lcd.bg_palette), lcd.bg_colors),
+            ("OBP0", Some(lcd.obj_palette[0]), lcd.sp1_colors),
+            ("OBP1", Some(lcd.obj_palette[1]), lcd.sp2_colors),
+            ("LCD ", None, lcd.shades),
+        ];
+
+        for (n, (name, register, colors)) in rows.iter().enumerate() {
+            let y = DBG_CONTENT_Y + n * (swatch + DBG_MARGIN);
+            let label = match register {
+                Some(value) => format!("{} {:02X}", name, value),
+                None => name.to_string(),
+            };
+            draw_text(
+                &mut self.image,
+                &label,
+                DBG_MARGIN,
+                y,
+                DBG_SCALE,
+                TEXT_COLOR,
+            );
+
+            let x = DBG_MARGIN + text_width("OBP0 00 ", DBG_SCALE);
+            for (i, color) in colors.iter().enumerate() {
+                self.image
+                    .fill_rect(x + i * (swatch + 2), y, swatch, swatch, *color);
+            }
+        }
+    }
+
+    fn draw_timeline(&mut self, bus: &Bus) {
+        let events = &bus.events.frame;
+        let (width, height) = (TICKS_PER_LINE as usize, LINES_PER_FRAME as usize);
+        let mut grid = Image::new(width, height);
+
+        // background shows the STAT mode of every dot
+        let mut mode_color = NO_MODE_COLOR;
+        let mut position = 0;
+        for event in events.iter() {
+            if let EventKind::Mode(mode) = event.kind {
+                let end = event.ly as usize * width + event.dot as usize;
+                grid.pixels[position..end.max(position)].fill(mode_color);
+                position = end.max(position);
+                mode_color = MODE_COLORS[mode as usize];
             }
+        }
+        grid.pixels[position..].fill(mode_color);
 
-            for bit in (0..7).rev() {
-                let hi = (((byte1 & (1 << bit)) != 0) as u8) << 1;
-                let lo = ((byte2 & (1 << bit)) != 0) as u8;
+        if self.event_filter & EventFilter::Mode as u8 == 0 {
+            grid.pixels.fill(NO_MODE_COLOR);
+        }
 
-                let color_id = hi | lo;
-                let color = TILE_COLORS[color_id as usize];
+        for event in self.filtered(events) {
+            let color = match event.kind {
+                EventKind::Mode(_) => continue,
+                EventKind::Interrupt(_) => INTERRUPT_COLOR,
+                EventKind::IoWrite { .. } => IO_WRITE_COLOR,
+                EventKind::DmaStart(_) => DMA_COLOR,
+            };
+            grid.set(event.dot as usize, event.ly as usize, color);
+        }
 
-                // draw rectangle
-                let rect_x: i32 = x + (7 - bit) * SCALE;
-                let rect_y = y + tile_y as i32 / 2 * SCALE;
-                let rect_w = SCALE;
-                let rect_h = SCALE;
+        self.image
+            .blit_stretched(&grid, DBG_MARGIN, DBG_CONTENT_Y, 1, TIMELINE_ROW);
 
-                let rect = Rect::new(rect_x, rect_y, rect_w as u32, rect_h as u32);
-                canvas.set_draw_color(color);
-                canvas.fill_rect(rect).unwrap();
+        let mut y = DBG_CONTENT_Y + height * TIMELINE_ROW + DBG_MARGIN;
+        let mut x = DBG_MARGIN;
+        for (filter, name, color) in EVENT_FILTERS.iter() {
+            let color = if self.event_filter & *filter as u8 != 0 {
+                *color
+            } else {
+                DIM_TEXT_COLOR
+            };
+            draw_text(&mut self.image, name, x, y, DBG_SCALE, color);
+            x += text_width(name, DBG_SCALE) + 2 * DBG_MARGIN;
+        }
+        y += 2 * DBG_MARGIN;
+
+        // events on the hovered line close to the hovered dot
+        let (dot, ly) = match self.hovered_dot() {
+            Some(position) => position,
+            None => {
+                let text = format!("{} EVENTS", self.filtered(events).count());
+                draw_text(&mut self.image, &text, DBG_MARGIN, y, DBG_SCALE, TEXT_COLOR);
+                return;
             }
+        };
+
+        self.image.fill_rect(
+            DBG_MARGIN + dot as usize,
+            DBG_CONTENT_Y,
+            1,
+            height * TIMELINE_ROW,
+            TEXT_COLOR,
+        );
+
+        let mode = events
+            .iter()
+            .take_while(|event| (event.ly, event.dot) <= (ly, dot))
+            .filter_map(|event| match event.kind {
+                EventKind::Mode(mode) => Some(MODE_NAMES[mode as usize]),
+                _ => None,
+            })
+            .last()
+            .unwrap_or("-");
+        let text = format!("LY {} DOT {} {}", ly, dot, mode);
+        draw_text(&mut self.image, &text, DBG_MARGIN, y, DBG_SCALE, TEXT_COLOR);
+
+        let nearby: Vec<PpuEvent> = self
+            .filtered(events)
+            .filter(|event| event.ly == ly && event.dot.abs_diff(dot) <= TIMELINE_HOVER_DOTS)
+            .copied()
+            .take(TIMELINE_HOVER_LINES)
+            .collect();
+
+        for event in nearby.iter() {
+            y += 2 * DBG_MARGIN;
+            let text = format!("{:3} {}", event.dot, describe_event(event));
+            draw_text(&mut self.image, &text, DBG_MARGIN, y, DBG_SCALE, TEXT_COLOR);
         }
     }
 
-    #[inline(always)]
-    pub fn update(&mut self, bus: &Bus) {
-        let mut x_draw = 0;
-        let mut y_draw = 0;
-        let mut tile_num = 0;
-
-        let address = 0x8000;
-
-        for tile_y in 0..24 {
-            for tile_x in 0..16 {
-                Self::display_tile(
-                    bus,
-                    &mut self.0,
-                    address,
-                    tile_num,
-                    x_draw + (tile_x * SCALE),
-                    y_draw + (tile_y * SCALE),
-                );
-
-                x_draw += 8 * SCALE;
-                tile_num += 1;
+    fn filtered<'e>(&self, events: &'e [PpuEvent]) -> impl Iterator<Item = &'e PpuEvent> {
+        let mask = self.event_filter;
+        events
+            .iter()
+            .filter(move |event| mask & event.kind.filter() as u8 != 0)
+    }
+
+    /// (dot, LY) under the mouse on the timeline grid
+    fn hovered_dot(&self) -> Option<(u16, u8)> {
+        let (x, y) = self.mouse?;
+        // negative positions wrap around and fail the range check
+        let dot = (x as usize).checked_sub(DBG_MARGIN)?;
+        let line = (y as usize).checked_sub(DBG_CONTENT_Y)? / TIMELINE_ROW;
+
+        if dot >= TICKS_PER_LINE as usize || line >= LINES_PER_FRAME as usize {
+            return None;
+        }
+
+        Some((dot as u16, line as u8))
+    }
+
+    pub fn toggle_event_filter(&mut self, filter: EventFilter) {
+        self.event_filter ^= filter as u8;
+    }
+
+    fn title(&self, bus: &Bus) -> String {
+        let lcd = &bus.ppu.lcd;
+        let active = |map_area: u16| {
+            if lcd.bg_map_area() == map_area {
+                " BG"
+            } else {
+                ""
+            }
+        };
+
+        match self.page {
+            DebugPage::Tiles => "TILE DATA 8000-97FF".to_string(),
+            DebugPage::BgMap0 => format!(
+                "MAP 9800{} SCX {} SCY {}",
+                active(0x9800),
+                lcd.scroll_x,
+                lcd.scroll_y
+            ),
+            DebugPage::BgMap1 => format!(
+                "MAP 9C00{} SCX {} SCY {}",
+                active(0x9C00),
+                lcd.scroll_x,
+                lcd.scroll_y
+            ),
+            DebugPage::WindowMap => format!(
+                "WINDOW {:04X} WX {} WY {}{}",
+                lcd.win_map_area(),
+                lcd.win_x,
+                lcd.win_y,
+                if lcd.is_window_enabled() != 0 {
+                    ""
+                } else {
+                    " OFF"
+                }
+            ),
+            DebugPage::Oam => format!("OAM 8X{} PAL/XFLIP/YFLIP/BG", lcd.obj_height()),
+            DebugPage::Palettes => "PALETTES".to_string(),
+            DebugPage::Timeline => {
+                if bus.events.enabled {
+                    format!("TIMELINE {}X{}", TICKS_PER_LINE, LINES_PER_FRAME)
+                } else {
+                    "TIMELINE OFF".to_string()
+                }
             }
+        }
+    }
 
-            x_draw = 0;
-            y_draw += 8 * SCALE;
+    #[inline(always)]
+    pub fn update(&mut self, bus: &Bus) {
+        self.image
+            .fill_rect(0, 0, self.image.width, self.image.height, DBG_BACKGROUND);
+
+        let title = match bus.emu.cpu_lock {
+            Some(lock) => format!("CPU LOCKED AT PC={:04X}", lock.pc),
+            None => self.title(bus),
+        };
+        draw_text(
+            &mut self.image,
+            &title,
+            DBG_MARGIN,
+            DBG_MARGIN,
+            DBG_SCALE,
+            TEXT_COLOR,
+        );
+        let hint = "F9 >";
+        let hint_x = self.image.width - DBG_MARGIN - text_width(hint, DBG_SCALE);
+        draw_text(
+            &mut self.image,
+            hint,
+            hint_x,
+            DBG_MARGIN,
+            DBG_SCALE,
+            DIM_TEXT_COLOR,
+        );
+
+        match self.page {
+            DebugPage::Tiles => self.draw_tiles(bus),
+            DebugPage::BgMap0 => self.draw_map(bus, 0x9800),
+            DebugPage::BgMap1 => self.draw_map(bus, 0x9C00),
+            DebugPage::WindowMap => self.draw_window_map(bus),
+            DebugPage::Oam => self.draw_oam(bus),
+            DebugPage::Palettes => self.draw_palettes(bus),
+            DebugPage::Timeline => self.draw_timeline(bus),
         }
+
+        copy_to_canvas(
+            &mut self.canvas,
+            &self.image.pixels,
+            self.image.width,
+            self.image.height,
+        );
     }
 
     #[inline(always)]
     pub fn present(&mut self) {
-        self.0.present();
+        self.canvas.present();
     }
 }
 
 impl DebugWindow {
     #[inline(always)]
     pub fn clear(&mut self) {
-        self.0.clear();
+        self.canvas.clear();
     }
 
     #[inline(always)]
     pub fn set_draw_color(&mut self, color: Color) {
-        self.0.set_draw_color(color);
+        self.canvas.set_draw_color(color);
+    }
+}
+
+fn io_register_name(address: u16) -> &'static str {
+    match address {
+        0xFF00 => "P1",
+        0xFF01 => "SB",
+        0xFF02 => "SC",
+        0xFF04 => "DIV",
+        0xFF05 => "TIMA",
+        0xFF06 => "TMA",
+        0xFF07 => "TAC",
+        0xFF0F => "IF",
+        0xFF10..=0xFF3F => "APU",
+        0xFF40 => "LCDC",
+        0xFF41 => "STAT",
+        0xFF42 => "SCY",
+        0xFF43 => "SCX",
+        0xFF44 => "LY",
+        0xFF45 => "LYC",
+        0xFF46 => "DMA",
+        0xFF47 => "BGP",
+        0xFF48 => "OBP0",
+        0xFF49 => "OBP1",
+        0xFF4A => "WY",
+        0xFF4B => "WX",
+        0xFFFF => "IE",
+        _ => "",
+    }
+}
+
+fn describe_event(event: &PpuEvent) -> String {
+    match event.kind {
+        EventKind::Mode(mode) => format!("MODE {} {}", mode, MODE_NAMES[mode as usize]),
+        EventKind::Interrupt(flag) => {
+            format!("INT {}", INTERRUPT_NAMES[flag.trailing_zeros() as usize])
+        }
+        EventKind::IoWrite { address, value } => format!(
+            "{:04X} {} = {:02X}",
+            address,
+            io_register_name(address),
+            value
+        ),
+        EventKind::DmaStart(source) => format!("OAM DMA FROM {:02X}00", source),
     }
 }
 
@@ -5,19 +5,27 @@
 extern crate lib_gbemu;
 extern crate sdl2;
 
+mod font;
 mod gbscreen;
 mod utils;
 
 use lib_gbemu::{
     cartridge::rom::Rom,
     cpu::Cpu,
-    debug::GsSerial,
-    gpu::{GbWindow, X_RES, Y_RES},
-    io::input::Gamepad,
+    gpu::{
+        events::EventFilter,
+        filter::PostProcess,
+        inspector,
+        palette::{self, PalettePreset, Shades},
+        ppu::Renderer,
+        scaler::Scaler,
+        GbWindow, X_RES, Y_RES,
+    },
+    io::{input::Gamepad, serial::LogLink},
     memory::Bus,
 };
 
-use gbscreen::{DebugMode, DebugWindow, MainWindow};
+use gbscreen::{DebugMode, DebugPage, DebugWindow, MainWindow};
 
 use sdl2::{
     event::{Event, WindowEvent},
@@ -25,12 +33,12 @@ use sdl2::{
     pixels::Color,
 };
 
-use std::env;
+use std::{env, io};
 
 const SCALE: i32 = 3;
-const DGB_SERIAL: bool = false;
-const DBG_SCREEN_WIDTH: i32 = (16 * 8 * SCALE) + (16 * SCALE);
-const DBG_SCREEN_HEIGHT: i32 = (32 * 8 * SCALE) + (32 * SCALE);
+// 256x256 maps at 2x plus margins, tall enough for 20 OAM rows
+const DBG_SCREEN_WIDTH: i32 = 256 * 2 + 16;
+const DBG_SCREEN_HEIGHT: i32 = 24 + 20 * 34 + 16;
 
 struct Emulator<'a>(Cpu, Bus<'a>);
 
@@ -50,11 +58,84 @@ fn on_key(gamepad: &mut Gamepad, bus: &mut Bus, keycode: Keycode, down: bool) {
     bus.gamepad.set_state(*state);
 }
 
-fn ui_init() -> (MainWindow, DebugWindow, sdl2::EventPump) {
+fn on_debug_key(bus: &mut Bus, keycode: Keycode) {
+    let layers = &mut bus.ppu.layers;
+    match keycode {
+        Keycode::F1 => layers.background = !layers.background,
+        Keycode::F2 => layers.window = !layers.window,
+        Keycode::F3 => layers.sprites = !layers.sprites,
+        Keycode::F4 => layers.sprite_limit = !layers.sprite_limit,
+        Keycode::F5 => layers.overlay = !layers.overlay,
+        _ => return,
+    };
+    println!("LAYERS: {:?}", layers);
+}
+
+fn on_timeline_key(debug_window: &mut DebugWindow, keycode: Keycode) {
+    if debug_window.page != DebugPage::Timeline {
+        return;
+    }
+
+    match keycode {
+        Keycode::Num1 => debug_window.toggle_event_filter(EventFilter::Mode),
+        Keycode::Num2 => debug_window.toggle_event_filter(EventFilter::Interrupt),
+        Keycode::Num3 => debug_window.toggle_event_filter(EventFilter::IoWrite),
+        Keycode::Num4 => debug_window.toggle_event_filter(EventFilter::DmaStart),
+        _ => {}
+    }
+}
+
+fn on_filter_key(post_process: &mut PostProcess, keycode: Keycode) {
+    match keycode {
+        Keycode::F6 => post_process.blending = !post_process.blending,
+        Keycode::F7 => post_process.dot_matrix = !post_process.dot_matrix,
+        Keycode::F8 => post_process.color_correction = !post_process.color_correction,
+        _ => return,
+    };
+    println!(
+        "FILTERS: blend {} dot-matrix {} color-correction {}",
+        post_process.blending, post_process.dot_matrix, post_process.color_correction
+    );
+}
+
+// F10 writes the tile data, both BG maps and the OAM sprites as PNGs
+fn dump_vram(bus: &Bus) {
+    let frame = bus.ppu.current_frame;
+    let tiles = format!("gbemu_tiles_{}.png", frame);
+    let map0 = format!("gbemu_map_9800_{}.png", frame);
+    let map1 = format!("gbemu_map_9C00_{}.png", frame);
+    let sprites = format!("gbemu_sprites_{}.png", frame);
+
+    let results = [
+        (&tiles, inspector::save_tile_data(&bus.ppu, &tiles)),
+        (&map0, inspector::save_tile_map(&bus.ppu, 0x9800, &map0)),
+        (&map1, inspector::save_tile_map(&bus.ppu, 0x9C00, &map1)),
+        (&sprites, inspector::save_sprites(&bus.ppu, &sprites)),
+    ];
+
+    for (path, result) in results.iter() {
+        match result {
+            Ok(()) => println!("SAVED: {}", path),
+            Err(err) => eprintln!("SAVE {}: {}", path, err),
+        }
+    }
+}
+
+// SCALE rounded up to a multiple of the scaler factor, so the scaled frame is
+// stretched over the window by a whole number
+fn window_scale(scaler: Scaler) -> i32 {
+    let factor = scaler.factor() as i32;
+
+    (SCALE + factor - 1) / factor * factor
+}
+
+fn ui_init(scale: i32) -> (MainWindow, DebugWindow, sdl2::EventPump) {
+    sdl2::hint::set("SDL_RENDER_SCALE_QUALITY", "0");
+
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
     let window = video_subsystem
-        .window("gbemu", (X_RES * SCALE) as u32, (Y_RES * SCALE) as u32)
+        .window("gbemu", (X_RES * scale) as u32, (Y_RES * scale) as u32)
         .position_centered()
         .build()
         .unwrap();
@@ -71,12 +152,12 @@ fn ui_init() -> (MainWindow, DebugWindow, sdl2::EventPump) {
 
     (
         MainWindow::new(window.into_canvas().build().unwrap()),
-        DebugWindow(debug_window.into_canvas().build().unwrap()),
+        DebugWindow::new(debug_window.into_canvas().build().unwrap()),
         event_pump,
     )
 }
 
-fn create_emu(path: String, screen: &mut dyn GbWindow) -> Result<Emulator, &'static str> {
+fn create_emu(path: String, screen: &mut dyn GbWindow) -> Result<Emulator<'_>, &'static str> {
     let (rom, header) = Rom::load(path)?;
     println!("{header}");
 
@@ -86,33 +167,91 @@ fn create_emu(path: String, screen: &mut dyn GbWindow) -> Result<Emulator, &'sta
     Ok(Emulator(cpu, bus))
 }
 
-fn emu_step(cpu: &mut Cpu, bus: &mut Bus, debug: &mut GsSerial) -> bool {
+fn emu_step(cpu: &mut Cpu, bus: &mut Bus) -> bool {
     if bus.emu.paused {
         return true;
     }
 
+    let was_locked = cpu.is_locked;
     cpu.step(bus);
 
-    if DGB_SERIAL {
-        debug.update(bus);
-        debug.print();
+    if cpu.is_locked && !was_locked {
+        if let Some(lock) = bus.emu.cpu_lock {
+            eprintln!("{lock}");
+        }
     }
 
     true
 }
 
+// --palette accepts a preset name or a path to a palette file
+fn parse_palette_arg(value: &str) -> Shades {
+    match PalettePreset::from_name(value) {
+        Some(preset) => preset.shades(),
+        None => palette::load_palette(value).unwrap_or_else(|err| {
+            eprintln!("PALETTE {}: {}", value, err);
+            PalettePreset::default().shades()
+        }),
+    }
+}
+
 fn main() {
-    let args = env::args();
-    let path: String = args.last().expect("<PATH> - path to the file");
+    let mut args = env::args().skip(1);
+    let mut path: Option<String> = None;
+    let mut shades = PalettePreset::default().shades();
+    // the palette key cycles on from here
+    let mut preset = PalettePreset::default();
+    let mut renderer = Renderer::default();
+    let mut post_process = PostProcess::new();
+    let mut scaler = Scaler::default();
+    let mut serial_log = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--palette" => {
+                let value = args
+                    .next()
+                    .expect("--palette <grayscale|dmg|pocket|light|FILE>");
+                shades = parse_palette_arg(&value);
+                if let Some(chosen) = PalettePreset::from_name(&value) {
+                    preset = chosen;
+                }
+            }
+            "--serial-log" => serial_log = true,
+            "--blend" => post_process.blending = true,
+            "--dot-matrix" => post_process.dot_matrix = true,
+            "--color-correction" => post_process.color_correction = true,
+            "--scaler" => {
+                scaler = args
+                    .next()
+                    .as_deref()
+                    .and_then(Scaler::from_name)
+                    .expect("--scaler <nearest|scale2x|scale3x|hq2x|xbr>");
+            }
+            "--renderer" => {
+                renderer = match args.next().as_deref() {
+                    Some("fifo") => Renderer::Fifo,
+                    Some("scanline") => Renderer::Scanline,
+                    _ => panic!("--renderer <fifo|scanline>"),
+                };
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    let scale = window_scale(scaler);
+    post_process.dot_matrix_scale = scale as usize;
+
+    let path: String = path.expect("<PATH> - path to the file");
     println!("PATH: {}", path);
 
     lib_gbemu::common::init_logger();
-    
+
     std::thread::Builder::new()
         .stack_size(1024 * 1024 * 8)
         .name("SDL Thread".to_string())
         .spawn(move || {
-            let (mut main_window, mut debug_window, mut event_pump) = ui_init();
+            let (mut main_window, mut debug_window, mut event_pump) = ui_init(scale);
             main_window.set_draw_color(Color::BLACK);
             main_window.clear();
             main_window.present();
@@ -120,13 +259,22 @@ fn main() {
             debug_window.set_draw_color(Color::RGB(17, 17, 17));
             debug_window.clear();
             debug_window.present();
-            drop(debug_window);
 
-            let mut emulator_window = main_window;
+            let mut emulator_window = DebugMode {
+                main_window,
+                debug_window,
+                is_updated: false,
+            };
+            emulator_window.main_window.post_process = post_process;
+            emulator_window.main_window.scaler = scaler;
 
             let Emulator(mut cpu, mut bus) =
                 create_emu(path, make_mut_ref!(&mut emulator_window)).unwrap();
-            let mut serial = GsSerial::new();
+            bus.ppu.lcd.set_shades(shades);
+            bus.ppu.renderer = renderer;
+            if serial_log {
+                bus.serial.link = Box::new(LogLink::new(io::stdout()));
+            }
 
             let mut gamepad = Gamepad::new();
 
@@ -142,10 +290,46 @@ fn main() {
                             keycode: Some(Keycode::Escape),
                             ..
                         } => break 'gb_loop,
+                        Event::KeyDown {
+                            keycode: Some(Keycode::P),
+                            ..
+                        } => {
+                            preset = preset.next();
+                            println!("PALETTE: {}", preset.name());
+                            bus.ppu.lcd.set_shades(preset.shades());
+                        }
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F10),
+                            ..
+                        } => dump_vram(&bus),
+                        Event::KeyDown {
+                            keycode: Some(Keycode::F9),
+                            ..
+                        } => {
+                            let debug_window = &mut emulator_window.debug_window;
+                            debug_window.page = debug_window.page.next();
+                            bus.events.enabled = debug_window.page == DebugPage::Timeline;
+                            bus.events.clear();
+                        }
+                        Event::MouseMotion {
+                            window_id, x, y, ..
+                        } => {
+                            let debug_window = &mut emulator_window.debug_window;
+                            debug_window.mouse = if window_id == debug_window.canvas.window().id() {
+                                Some((x, y))
+                            } else {
+                                None
+                            };
+                        }
                         Event::KeyDown {
                             keycode: Some(keycode),
                             ..
-                        } => on_key(&mut gamepad, &mut bus, keycode, true),
+                        } => {
+                            on_debug_key(&mut bus, keycode);
+                            on_timeline_key(&mut emulator_window.debug_window, keycode);
+                            on_filter_key(&mut emulator_window.main_window.post_process, keycode);
+                            on_key(&mut gamepad, &mut bus, keycode, true);
+                        }
                         Event::KeyUp {
                             keycode: Some(keycode),
                             ..
@@ -154,14 +338,14 @@ fn main() {
                     }
                 }
                 bus.gamepad.set_state(gamepad.state);
-                if !emu_step(&mut cpu, &mut bus, &mut serial) {
+                if !emu_step(&mut cpu, &mut bus) {
                     return;
                 };
-                // if emulator_window.is_updated {
-                //     emulator_window.debug_window.update(&bus);
-                //     emulator_window.debug_window.present();
-                //     emulator_window.is_updated = false;
-                // }
+                if emulator_window.is_updated {
+                    emulator_window.debug_window.update(&bus);
+                    emulator_window.debug_window.present();
+                    emulator_window.is_updated = false;
+                }
             }
         })
         .unwrap()
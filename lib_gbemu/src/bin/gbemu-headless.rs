@@ -0,0 +1,114 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Runs a ROM without a window for a number of frames, then dumps the last
+// frame and VRAM as PNGs
+//
+
+extern crate lib_gbemu;
+
+use lib_gbemu::{
+    cartridge::rom::Rom,
+    cpu::Cpu,
+    gpu::{
+        inspector::{self, Image},
+        png, NullWindow, X_RES, Y_RES,
+    },
+    io::serial::LogLink,
+    memory::Bus,
+};
+
+use std::{env, io, process};
+
+const DEFAULT_FRAMES: u32 = 60;
+const USAGE: &str = "usage: gbemu-headless [--frames N] [--serial-log] [--registers] \
+                     [--screenshot FILE] [--tiles FILE] [--bg-map <9800|9C00> FILE] \
+                     [--sprites FILE] <ROM>";
+
+enum Dump {
+    Screen(String),
+    Tiles(String),
+    TileMap(u16, String),
+    Sprites(String),
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn screenshot(bus: &Bus) -> Image {
+    Image {
+        width: X_RES as usize,
+        height: Y_RES as usize,
+        pixels: bus.ppu.video_buffer.to_vec(),
+    }
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let mut path: Option<String> = None;
+    let mut frames = DEFAULT_FRAMES;
+    let mut dumps = Vec::new();
+    let mut serial_log = false;
+    let mut registers = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| fail(USAGE));
+
+        match arg.as_str() {
+            "--frames" => frames = value().parse().unwrap_or_else(|_| fail(USAGE)),
+            "--serial-log" => serial_log = true,
+            "--registers" => registers = true,
+            "--screenshot" => dumps.push(Dump::Screen(value())),
+            "--tiles" => dumps.push(Dump::Tiles(value())),
+            "--sprites" => dumps.push(Dump::Sprites(value())),
+            "--bg-map" => {
+                let map_area = u16::from_str_radix(value().trim_start_matches("0x"), 16)
+                    .unwrap_or_else(|_| fail(USAGE));
+                dumps.push(Dump::TileMap(map_area, value()));
+            }
+            _ => path = Some(arg),
+        }
+    }
+
+    let path = path.unwrap_or_else(|| fail(USAGE));
+    let (rom, _) = Rom::load(path).unwrap_or_else(|err| fail(err));
+    let mut window = NullWindow;
+    let mut cpu = Cpu::new();
+    let mut bus = Bus::new(rom, &mut window);
+    if serial_log {
+        bus.serial.link = Box::new(LogLink::new(io::stdout()));
+    }
+
+    while bus.ppu.current_frame < frames && bus.emu.cpu_lock.is_none() {
+        cpu.step(&mut bus);
+    }
+
+    if let Some(lock) = bus.emu.cpu_lock {
+        fail(&lock.to_string());
+    }
+
+    // Mooneye test ROMs report their result in B, C, D, E, H and L
+    if registers {
+        let regs = &cpu.regs;
+        println!(
+            "A={:02X} F={:02X} B={:02X} C={:02X} D={:02X} E={:02X} H={:02X} L={:02X} SP={:04X} PC={:04X}",
+            regs.a, regs.f, regs.b, regs.c, regs.d, regs.e, regs.h, regs.l, regs.sp, regs.pc
+        );
+    }
+
+    for dump in dumps.iter() {
+        let result = match dump {
+            Dump::Screen(path) => png::save(&screenshot(&bus), path),
+            Dump::Tiles(path) => inspector::save_tile_data(&bus.ppu, path),
+            Dump::TileMap(map_area, path) => inspector::save_tile_map(&bus.ppu, *map_area, path),
+            Dump::Sprites(path) => inspector::save_sprites(&bus.ppu, path),
+        };
+
+        if let Err(err) = result {
+            fail(err);
+        }
+    }
+}
@@ -51,14 +51,19 @@ impl Rom {
         }
     }
 
-    pub fn read(&self, address: u16) -> u8 {
-        self.data[address as usize]
+    /// CGB flag at 0x143, CGB-only and dual mode cartridges get CGB registers
+    pub fn supports_cgb(&self) -> bool {
+        self.read(LOCATION_CGB_FLAG as u16) & 0x80 != 0
     }
 
-    pub fn write(&mut self, _address: u16, _value: u8) {
-        eprintln!("for now ROM only");
+    // no cartridge RAM yet, anything past the ROM data is open bus
+    pub fn read(&self, address: u16) -> u8 {
+        self.data.get(address as usize).copied().unwrap_or(0xFF)
     }
 
+    // for now ROM only, writes are ignored
+    pub fn write(&mut self, _address: u16, _value: u8) {}
+
     fn calculate_cecksum(&self) -> u8 {
         let mut x: u16 = 0;
         for i in 0x0134..=0x014C {
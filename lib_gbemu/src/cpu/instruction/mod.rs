@@ -1671,7 +1671,7 @@ impl Instruction {
                 ..Instruction::default()
             };
             inst[0xE9] = Instruction {
-                in_type: IT::Jp,
+                in_type: IT::Jphl,
                 mode: AM::Reg,
                 r1: RT::HL,
                 ..Instruction::default()
@@ -1772,7 +1772,22 @@ impl Instruction {
                 ..Instruction::default()
             };
         }
-        
+
+        // illegal opcodes hang the CPU
+        {
+            let illegal = [
+                0xD3, 0xDB, 0xDD, 0xE3, 0xE4, 0xEB, 0xEC, 0xED, 0xF4, 0xFC, 0xFD,
+            ];
+            let mut i = 0;
+            while i < illegal.len() {
+                inst[illegal[i]] = Instruction {
+                    in_type: IT::Err,
+                    ..Instruction::default()
+                };
+                i += 1;
+            }
+        }
+
         inst
     }
 }
@@ -1791,6 +1806,44 @@ impl From<u8> for Instruction {
     }
 }
 
+impl Instruction {
+    /// Decodes the byte after a 0xCB prefix, `param` is the bit number of BIT/RES/SET
+    pub fn from_cb(code: u8) -> Self {
+        use self::InstructionType as IT;
+
+        const SHIFTS: [InstructionType; 8] = [
+            IT::Rlc,
+            IT::Rrc,
+            IT::Rl,
+            IT::Rr,
+            IT::Sla,
+            IT::Sra,
+            IT::Swap,
+            IT::Srl,
+        ];
+
+        let bit = (code >> 3) & 0b111;
+        let r1 = RegisterType::decode(code & 0b111);
+
+        Self {
+            in_type: match code >> 6 {
+                0 => SHIFTS[bit as usize],
+                1 => IT::Bit,
+                2 => IT::Res,
+                _ => IT::Set,
+            },
+            mode: if r1 == RegisterType::HL {
+                AddressMode::Mem
+            } else {
+                AddressMode::Reg
+            },
+            r1,
+            param: bit,
+            ..Instruction::default()
+        }
+    }
+}
+
 impl RegisterType {
     fn decode(value: u8) -> Self {
         use self::RegisterType as RT;
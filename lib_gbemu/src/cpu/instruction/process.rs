@@ -4,15 +4,14 @@
 use self::instruction::{AddressMode as AM, ConditionType as CT, InstructionType as IT};
 use self::regs::CpuFlag as Flag;
 use crate::cpu::*;
+use crate::emu::CpuLock;
+use crate::io::speed::SWITCH_CYCLES;
 use crate::memory::*;
 
 impl Cpu {
     pub fn execute(&mut self, bus: &mut Bus) {
         match self.cur_inst.in_type {
-            IT::None => panic!(
-                "INVALID INSTRUCTION: {:?}, {}",
-                self.cur_inst, self.cur_opcode
-            ),
+            IT::None | IT::Err => self.lock_in(bus),
             IT::Nop => self.nop_in(),
             IT::Ld => self.ld_in(bus),
             IT::Inc => self.inc_in(bus),
@@ -22,14 +21,14 @@ impl Cpu {
             IT::Sbc => self.sbc_in(),
             IT::Rlca => self.rlca_in(),
             IT::Rrca => self.rrca_in(),
-            IT::Stop => self.stop_in(),
+            IT::Stop => self.stop_in(bus),
             IT::Rla => self.rla_in(),
             IT::Rra => self.rra_in(),
             IT::Daa => self.daa_in(),
             IT::Cpl => self.cpl_in(),
             IT::Scf => self.scf_in(),
             IT::Ccf => self.ccf_in(),
-            IT::Halt => self.halt_in(),
+            IT::Halt => self.halt_in(bus),
             IT::Adc => self.adc_in(),
             IT::And => self.and_in(),
             IT::Xor => self.xor_in(),
@@ -44,40 +43,51 @@ impl Cpu {
             IT::Cb => self.cb_in(bus),
             IT::Call => self.call_in(bus),
             IT::Ldh => self.ldh_in(bus),
-            IT::Jphl => todo!(),
+            IT::Jphl => self.jphl_in(),
             IT::Di => self.di_in(),
             IT::Ei => self.ei_in(),
             IT::Rst => self.rst_in(bus),
-            IT::Err => todo!(),
-            IT::Rlc => todo!(),
-            IT::Rrc => todo!(),
-            IT::Rl => todo!(),
-            IT::Rr => todo!(),
-            IT::Sla => todo!(),
-            IT::Sra => todo!(),
-            IT::Swap => todo!(),
-            IT::Srl => todo!(),
-            IT::Bit => todo!(),
-            IT::Res => todo!(),
-            IT::Set => todo!(),
+            IT::Rlc => self.rlc_in(bus),
+            IT::Rrc => self.rrc_in(bus),
+            IT::Rl => self.rl_in(bus),
+            IT::Rr => self.rr_in(bus),
+            IT::Sla => self.sla_in(bus),
+            IT::Sra => self.sra_in(bus),
+            IT::Swap => self.swap_in(bus),
+            IT::Srl => self.srl_in(bus),
+            IT::Bit => self.bit_in(bus),
+            IT::Res => self.res_in(bus),
+            IT::Set => self.set_in(bus),
         }
     }
 
     #[inline(always)]
     fn nop_in(&self) {}
 
+    // illegal opcode: the CPU stops for good, only a reset gets it back
+    fn lock_in(&mut self, bus: &mut Bus) {
+        self.is_locked = true;
+        bus.emu.cpu_lock = Some(CpuLock {
+            pc: self.regs.pc.wrapping_sub(1),
+            opcode: self.cur_opcode,
+        });
+    }
+
     fn ld_in(&mut self, bus: &mut Bus) {
         if self.dest_is_mem {
             //e.g.: LD (BC) A
             if self.cur_inst.r2.is_16bit() {
-                bus.cycle(1);
-                bus.write16(self.mem_dest, self.fetched_data);
+                // LD (a16), SP: low byte first
+                self.write_cycle(bus, self.mem_dest, self.fetched_data as u8);
+                self.write_cycle(
+                    bus,
+                    self.mem_dest.wrapping_add(1),
+                    (self.fetched_data >> 8) as u8,
+                );
             } else {
-                bus.write(self.mem_dest, self.fetched_data as u8);
+                self.write_cycle(bus, self.mem_dest, self.fetched_data as u8);
             }
 
-            bus.cycle(1);
-
             return;
         }
 
@@ -93,36 +103,40 @@ impl Cpu {
                 self.read_reg(self.cur_inst.r2)
                     .wrapping_add_signed(fetched_data as i8 as i16),
             );
+            bus.cycle(1);
 
             return;
         }
 
+        // LD SP, HL
+        if self.cur_inst.mode == AM::RegReg && self.cur_inst.r1.is_16bit() {
+            bus.cycle(1);
+        }
+
         self.set_reg(self.cur_inst.r1, self.fetched_data);
     }
 
     fn ldh_in(&mut self, bus: &mut Bus) {
         match self.cur_inst.r1 {
             RT::A => {
-                let data = bus.read(0xFF00 | self.fetched_data) as u16;
+                let data = self.read_cycle(bus, 0xFF00 | self.fetched_data) as u16;
                 self.set_reg(self.cur_inst.r1, data);
             }
             _ => {
-                bus.write(self.mem_dest, self.regs.a);
+                self.write_cycle(bus, self.mem_dest, self.regs.a);
             }
         }
-
-        bus.cycle(1);
     }
 
     fn goto_in(&mut self, bus: &mut Bus, address: u16, pushpc: bool) {
         if self.check_cond() {
+            bus.cycle(1);
+
             if pushpc {
-                bus.cycle(2);
                 self.stack_push16(self.regs.pc, bus);
             }
 
             self.regs.pc = address;
-            bus.cycle(1);
         }
     }
 
@@ -130,6 +144,12 @@ impl Cpu {
         self.goto_in(bus, self.fetched_data, false);
     }
 
+    // JP (HL) takes a single M-cycle, no extra cycle for the jump
+    #[inline(always)]
+    fn jphl_in(&mut self) {
+        self.regs.pc = self.fetched_data;
+    }
+
     fn jr_in(&mut self, bus: &mut Bus) {
         let rel = (self.fetched_data & 0xFF) as i8;
         let addr = self.regs.pc.wrapping_add(rel as u16);
@@ -152,9 +172,7 @@ impl Cpu {
 
         if self.check_cond() {
             let lo = self.stack_pop(bus);
-            bus.cycle(1);
             let hi = self.stack_pop(bus);
-            bus.cycle(1);
 
             let value = ((hi as u16) << 8) | (lo as u16);
             self.regs.pc = value;
@@ -172,16 +190,16 @@ impl Cpu {
     fn inc_in(&mut self, bus: &mut Bus) {
         let mut val = self.read_reg(self.cur_inst.r1).wrapping_add(1);
 
-        if self.cur_inst.r1.is_16bit() {
-            bus.cycle(1);
-        }
-
-        if self.cur_inst.r1 == RT::HL && self.cur_inst.mode == AM::Mem {
-            let reg_hl = self.read_reg(RT::HL);
-            val = ((bus.read(reg_hl)) as u16).wrapping_add(1);
-            val &= 0xFF;
-            bus.write(reg_hl, val as u8);
+        if self.cur_inst.mode == AM::Mem {
+            // INC (HL): the read was done by fetch_data
+            val = (self.fetched_data as u8).wrapping_add(1) as u16;
+            self.write_cycle(bus, self.mem_dest, val as u8);
         } else {
+            if self.cur_inst.r1.is_16bit() {
+                bus.ppu.oam_bug_write(self.read_reg(self.cur_inst.r1));
+                bus.cycle(1);
+            }
+
             self.set_reg(self.cur_inst.r1, val);
             val = self.read_reg(self.cur_inst.r1);
         }
@@ -198,15 +216,16 @@ impl Cpu {
     fn dec_in(&mut self, bus: &mut Bus) {
         let mut val = self.read_reg(self.cur_inst.r1).wrapping_sub(1);
 
-        if self.cur_inst.r1.is_16bit() {
-            bus.cycle(1);
-        }
-
-        if self.cur_inst.r1 == RT::HL && self.cur_inst.mode == AM::Mem {
-            let reg_hl = self.read_reg(RT::HL);
-            val = (bus.read(reg_hl) - 1) as u16;
-            bus.write(reg_hl, val as u8);
+        if self.cur_inst.mode == AM::Mem {
+            // DEC (HL): the read was done by fetch_data
+            val = (self.fetched_data as u8).wrapping_sub(1) as u16;
+            self.write_cycle(bus, self.mem_dest, val as u8);
         } else {
+            if self.cur_inst.r1.is_16bit() {
+                bus.ppu.oam_bug_write(self.read_reg(self.cur_inst.r1));
+                bus.cycle(1);
+            }
+
             self.set_reg(self.cur_inst.r1, val);
             val = self.read_reg(self.cur_inst.r1);
         }
@@ -228,14 +247,18 @@ impl Cpu {
         let is_16bit: bool = self.cur_inst.r1.is_16bit();
         let is_sp: bool = self.cur_inst.r1 == RT::SP;
         let val: u32 = if is_sp {
-            (reg_1 + self.fetched_data as i8 as u16) as u32
+            reg_1.wrapping_add(self.fetched_data as i8 as u16) as u32
         } else {
-            (reg_1 + self.fetched_data) as u32
+            reg_1 as u32 + self.fetched_data as u32
         };
 
+        // ADD HL, rr takes one internal cycle, ADD SP, e8 two
         if is_16bit {
             bus.cycle(1);
         }
+        if is_sp {
+            bus.cycle(1);
+        }
 
         let (z, h, c) = if is_sp {
             (
@@ -304,10 +327,13 @@ impl Cpu {
 
     fn sbc_in(&mut self) {
         let flag_c = self.regs.flag_c();
-        let val = (self.fetched_data + (flag_c as u16)) as u8;
         let reg_1 = self.read_reg(self.cur_inst.r1);
+        let result = reg_1
+            .wrapping_sub(self.fetched_data)
+            .wrapping_sub(flag_c as u16)
+            & 0xFF;
 
-        let flag_z = (reg_1 - val as u16) == 0;
+        let flag_z = result == 0;
         let flag_h = (reg_1 as i32 & 0xF)
             .wrapping_sub(self.fetched_data as i32 & 0xF)
             .wrapping_sub(flag_c as i32)
@@ -317,7 +343,7 @@ impl Cpu {
             .wrapping_sub(flag_c as i32)
             < 0;
 
-        self.set_reg(self.cur_inst.r1, reg_1 - val as u16);
+        self.set_reg(self.cur_inst.r1, result);
 
         self.regs.set_flag(Flag::Z, flag_z);
         self.regs.set_flag(Flag::N, true);
@@ -357,155 +383,97 @@ impl Cpu {
         self.regs.set_flags(flag_z, flag_n, flag_h, flag_c);
     }
 
+    // CB prefix: decode the second byte and run it as its own instruction
     fn cb_in(&mut self, bus: &mut Bus) {
-        let operation = self.fetched_data as u8;
-        let reg = RT::decode(operation & 0b111);
-        let mut reg_val = self.read_reg8(reg, bus);
-        let bit = (operation >> 3) & 0b111;
-        let bit_op = (operation >> 6) & 0b11;
-        let flag_c = self.regs.flag_c();
+        self.cur_inst = Instruction::from_cb(self.fetched_data as u8);
+        self.execute(bus);
+    }
 
-        bus.cycle(1);
+    /// CB operand, reading (HL) takes an extra M-cycle
+    fn cb_read(&mut self, bus: &mut Bus) -> u8 {
+        match self.cur_inst.r1 {
+            RT::HL => self.read_cycle(bus, self.read_reg(RT::HL)),
+            reg => self.read_reg8(reg, bus),
+        }
+    }
 
-        if reg == RT::HL {
-            bus.cycle(2);
+    fn cb_write(&mut self, bus: &mut Bus, value: u8) {
+        match self.cur_inst.r1 {
+            RT::HL => self.write_cycle(bus, self.read_reg(RT::HL), value),
+            reg => self.set_reg8(reg, value, bus),
         }
+    }
 
-        match bit_op {
-            1 => {
-                // BIT
-                let flag_z = reg_val & (1 << bit) == 0;
-                self.regs.set_flag(Flag::Z, flag_z);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, true);
-                return;
-            }
-            2 => {
-                // RST
-                reg_val &= !(1 << bit);
-                self.set_reg8(reg, reg_val, bus);
-                return;
-            }
-            3 => {
-                // SET
-                reg_val |= !(1 << bit);
-                self.set_reg8(reg, reg_val, bus);
-                return;
-            }
-            _ => {}
-        };
+    fn cb_shift(&mut self, bus: &mut Bus, result: u8, flag_c: bool) {
+        self.cb_write(bus, result);
+        self.regs.set_flags(result == 0, false, false, flag_c);
+    }
 
-        match bit {
-            0 => {
-                // RLC
-                let mut flag_c = false;
-                #[allow(clippy::identity_op)]
-                let mut result = (reg_val << 1) & 0xFF; // (reg_val << 1) & 0xFF;
+    fn rlc_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_shift(bus, value.rotate_left(1), value & 0x80 != 0);
+    }
 
-                if (reg_val & (1 << 7)) != 0 {
-                    result |= 1;
-                    flag_c = true;
-                }
+    fn rrc_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_shift(bus, value.rotate_right(1), value & 1 != 0);
+    }
 
-                self.set_reg8(reg, result, bus);
+    fn rl_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        let result = (value << 1) | self.regs.flag_c() as u8;
+        self.cb_shift(bus, result, value & 0x80 != 0);
+    }
 
-                self.regs.set_flag(Flag::Z, result == 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, flag_c);
-            }
-            1 => {
-                // RRC
-                let old = reg_val;
-                reg_val >>= 1;
-                reg_val |= old << 7;
-
-                self.set_reg8(reg, reg_val, bus);
-
-                self.regs.set_flag(Flag::Z, (!reg_val) != 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, (old & 1) != 0);
-            }
-            2 => {
-                // RL
-                let old = reg_val;
-                reg_val <<= 1;
-                reg_val |= flag_c as u8;
-
-                self.set_reg8(reg, reg_val, bus);
-
-                self.regs.set_flag(Flag::Z, (!reg_val) != 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, (!!(old & 0x80)) != 0)
-            }
-            3 => {
-                // RR
-                let old = reg_val;
-                reg_val >>= 1;
-                reg_val |= (flag_c as u8) << 7;
-
-                self.set_reg8(reg, reg_val, bus);
-
-                self.regs.set_flag(Flag::Z, reg_val == 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, (old & 1) != 0);
-            }
-            4 => {
-                // SLA
-                let old = reg_val;
-                reg_val <<= 1;
+    fn rr_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        let result = (value >> 1) | ((self.regs.flag_c() as u8) << 7);
+        self.cb_shift(bus, result, value & 1 != 0);
+    }
 
-                self.set_reg8(reg, reg_val, bus);
+    fn sla_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_shift(bus, value << 1, value & 0x80 != 0);
+    }
 
-                self.regs.set_flag(Flag::Z, reg_val == 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, (!!(old & 0x80)) != 0);
-            }
-            5 => {
-                // SRA
-                let result = ((reg_val as i8) >> 1) as u8; // Perform arithmetic shift right, preserving the sign bit
+    fn sra_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        // arithmetic shift keeps the sign bit
+        self.cb_shift(bus, (value >> 1) | (value & 0x80), value & 1 != 0);
+    }
 
-                self.set_reg8(reg, result, bus);
+    fn swap_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_shift(bus, value.rotate_left(4), false);
+    }
 
-                self.regs.set_flag(Flag::Z, result == 0);
-                self.regs.set_flag(Flag::N, false);
-                self.regs.set_flag(Flag::H, false);
-                self.regs.set_flag(Flag::C, (reg_val & 1) != 0);
-            }
-            6 => {
-                // SWAP
-                reg_val = ((reg_val & 0xF0) >> 4) | ((reg_val & 0xF) << 4);
-
-                self.set_reg8(reg, reg_val, bus);
-                self.regs.set_flag(Flag::Z, reg_val == 0); // Zero flag
-                self.regs.set_flag(Flag::N, false); // Subtraction flag
-                self.regs.set_flag(Flag::H, false); // Half-carry flag
-                self.regs.set_flag(Flag::C, false); // Carry flag
-            }
-            7 => {
-                // SRL
-                let result = reg_val >> 1;
+    fn srl_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_shift(bus, value >> 1, value & 1 != 0);
+    }
 
-                self.set_reg8(reg, result, bus);
+    fn bit_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
 
-                self.regs.set_flag(Flag::Z, result == 0); // Zero flag
-                self.regs.set_flag(Flag::N, false); // Subtraction flag
-                self.regs.set_flag(Flag::H, false); // Half-carry flag
-                self.regs.set_flag(Flag::C, (reg_val & 1) != 0); // Carry flag if LSB is 1
-            }
-            _ => panic!("INVALID CB INSTRUCTION: {:02X}", operation),
-        };
+        self.regs
+            .set_flag(Flag::Z, value & (1 << self.cur_inst.param) == 0);
+        self.regs.set_flag(Flag::N, false);
+        self.regs.set_flag(Flag::H, true);
+    }
+
+    fn res_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_write(bus, value & !(1 << self.cur_inst.param));
+    }
+
+    fn set_in(&mut self, bus: &mut Bus) {
+        let value = self.cb_read(bus);
+        self.cb_write(bus, value | (1 << self.cur_inst.param));
     }
 
     fn pop_in(&mut self, bus: &mut Bus) {
         let lo = self.stack_pop(bus);
-        bus.cycle(1);
         let hi = self.stack_pop(bus);
-        bus.cycle(1);
 
         let result = ((hi as u16) << 8) | (lo as u16);
         let reg_1 = self.cur_inst.r1;
@@ -518,24 +486,18 @@ impl Cpu {
     }
 
     fn push_in(&mut self, bus: &mut Bus) {
-        let hi = (self.read_reg(self.cur_inst.r1) >> 8) & 0xFF;
-        bus.cycle(1);
-        self.stack_push(hi as u8, bus);
-
-        let lo = self.read_reg(self.cur_inst.r1) & 0xFF;
-        bus.cycle(1);
-        self.stack_push(lo as u8, bus);
-
         bus.cycle(1);
+        self.stack_push16(self.read_reg(self.cur_inst.r1), bus);
     }
 
     #[inline(always)]
     fn di_in(&mut self) {
         self.interrupt_master_enabled = false;
+        self.enabling_ime = false;
     }
 
     fn ei_in(&mut self) {
-        self.enabling_ime = true;
+        self.enabling_ime = !self.interrupt_master_enabled;
     }
 
     fn rlca_in(&mut self) {
@@ -578,8 +540,32 @@ impl Cpu {
         self.regs.set_flag(Flag::C, new_carry != 0);
     }
 
-    fn stop_in(&mut self) {
-        panic!("STOP INSTRUCTION PROCESS");
+    // STOP freezes the timer and the LCD until a joypad line goes low, or
+    // switches the CGB speed when KEY1 is armed. The padding byte after it is
+    // skipped only without a pending interrupt. With a button already held it
+    // acts as HALT, or as NOP with an interrupt pending, and DIV keeps going.
+    fn stop_in(&mut self, bus: &mut Bus) {
+        let button_held = bus.gamepad.calculate_output() & 0x0F != 0x0F;
+        let pending = bus.interrupts.has_any_flag();
+
+        if !pending {
+            self.regs.pc = self.regs.pc.wrapping_add(1);
+        }
+
+        if button_held {
+            self.is_halted = !pending;
+            return;
+        }
+
+        match &mut bus.speed {
+            Some(speed) if speed.armed => {
+                speed.switch();
+                bus.cycle(SWITCH_CYCLES);
+            }
+            _ => bus.stopped = true,
+        }
+
+        bus.timer.write(0xFF04, 0);
     }
 
     fn daa_in(&mut self) {
@@ -627,7 +613,15 @@ impl Cpu {
         self.regs.set_flag(Flag::C, !flag_c);
     }
 
-    fn halt_in(&mut self) {
-        self.is_halted = true;
+    fn halt_in(&mut self, bus: &Bus) {
+        let ime = self.interrupt_master_enabled && !self.enabling_ime;
+
+        // with an interrupt already pending HALT never halts: IME on services
+        // it right away, IME off (or still delayed by EI) hits the HALT bug
+        if bus.interrupts.has_any_flag() {
+            self.halt_bug = !ime;
+        } else {
+            self.is_halted = true;
+        }
     }
 }
@@ -27,9 +27,15 @@ pub struct Cpu {
     pub cur_inst: Instruction,
 
     pub is_halted: bool,
+    // set by an illegal opcode, nothing wakes the CPU up again
+    pub is_locked: bool,
 
     pub interrupt_master_enabled: bool,
+    // EI was executed, IME turns on once the next instruction runs
     enabling_ime: bool,
+    // HALT with a pending interrupt and IME off: PC fails to move past the
+    // next byte, so it is read twice
+    pub halt_bug: bool,
 }
 
 impl Cpu {
@@ -42,16 +48,39 @@ impl Cpu {
             cur_opcode: 0,
             cur_inst: Instruction::default(),
             is_halted: false,
-            interrupt_master_enabled: true,
-            enabling_ime: true,
+            is_locked: false,
+            interrupt_master_enabled: false,
+            enabling_ime: false,
+            halt_bug: false,
         }
     }
 
     pub fn step(&mut self, bus: &mut Bus) {
+        if self.is_locked {
+            bus.cycle(1);
+            return;
+        }
+
+        // EI delay: IME is on while the instruction after EI runs, HALT still
+        // sees `enabling_ime` to trigger the HALT bug
+        let ime_delayed = self.enabling_ime;
+        if ime_delayed {
+            self.interrupt_master_enabled = true;
+        }
+
+        if bus.stopped {
+            bus.cycle(1);
+
+            // any selected joypad line going low ends STOP
+            if bus.gamepad.calculate_output() & 0x0F != 0x0F {
+                bus.stopped = false;
+            }
+            return;
+        }
+
         if !self.is_halted {
             // 65534
             self.fetch_instruction(bus);
-            bus.cycle(1);
             self.fetch_data(bus);
 
             if DEBUG {
@@ -84,33 +113,61 @@ impl Cpu {
                 common::debug_write(debug_data);
             }
 
-            if bus.timer.ticks == 1764640 {
-                print!("BREAK");
-            }
-
             self.execute(bus);
         } else {
             bus.cycle(1);
 
-            if bus.interrupts.flags != 0 {
+            // any pending IF & IE wakes the CPU, it is only serviced with IME on
+            if bus.interrupts.has_any_flag() {
                 self.is_halted = false;
             }
         }
 
-        if self.interrupt_master_enabled {
-            self.handle_interrupts(bus);
+        if ime_delayed {
             self.enabling_ime = false;
         }
 
-        if self.enabling_ime {
-            self.interrupt_master_enabled = true;
+        if self.interrupt_master_enabled {
+            self.handle_interrupts(bus);
         }
     }
 
-    pub fn fetch_instruction(&mut self, bus: &Bus) {
-        self.cur_opcode = bus.read(self.regs.pc);
-        self.cur_inst = Instruction::from(self.cur_opcode);
+    // every bus access takes one M-cycle: the access happens first, then
+    // timer, PPU and DMA advance by 4 T-cycles
+
+    fn read_cycle(&self, bus: &mut Bus, address: u16) -> u8 {
+        let value = bus.read(address);
+        bus.cycle(1);
+
+        value
+    }
+
+    fn write_cycle(&self, bus: &mut Bus, address: u16, value: u8) {
+        bus.write(address, value);
+        bus.cycle(1);
+    }
+
+    fn fetch_byte(&mut self, bus: &mut Bus) -> u8 {
+        let value = self.read_cycle(bus, self.regs.pc);
         self.regs.pc = self.regs.pc.wrapping_add(1);
+
+        value
+    }
+
+    fn fetch_word(&mut self, bus: &mut Bus) -> u16 {
+        let lo = self.fetch_byte(bus);
+        let hi = self.fetch_byte(bus);
+
+        bytes_to_word!(lo, hi)
+    }
+
+    pub fn fetch_instruction(&mut self, bus: &mut Bus) {
+        self.cur_opcode = self.fetch_byte(bus);
+        if self.halt_bug {
+            self.halt_bug = false;
+            self.regs.pc = self.regs.pc.wrapping_sub(1);
+        }
+        self.cur_inst = Instruction::from(self.cur_opcode);
     }
 
     pub fn fetch_data(&mut self, bus: &mut Bus) {
@@ -125,19 +182,11 @@ impl Cpu {
             AM::RegReg => {
                 self.fetched_data = self.read_reg(self.cur_inst.r2);
             }
-            AM::RegD8 => {
-                self.fetched_data = bus.read(self.regs.pc) as u16;
-                bus.cycle(1);
-                self.regs.pc += 1;
+            AM::RegD8 | AM::RegA8 | AM::HLRegSPReg | AM::D8 => {
+                self.fetched_data = self.fetch_byte(bus) as u16;
             }
             AM::D16 | AM::RegD16 => {
-                let lo = bus.read(self.regs.pc);
-                bus.cycle(1);
-                let hi = bus.read(self.regs.pc + 1);
-                bus.cycle(1);
-
-                self.fetched_data = bytes_to_word!(lo, hi);
-                self.regs.pc += 2;
+                self.fetched_data = self.fetch_word(bus);
             }
             AM::MemReg => {
                 self.fetched_data = self.read_reg(self.cur_inst.r2);
@@ -155,103 +204,61 @@ impl Cpu {
                     address |= 0xFF00;
                 }
 
-                self.fetched_data = bus.read(address) as u16;
-                bus.cycle(1);
+                self.fetched_data = self.read_cycle(bus, address) as u16;
             }
             AM::RegHLI => {
                 let address = self.read_reg(self.cur_inst.r2);
-                self.fetched_data = bus.read(address) as u16;
-                bus.cycle(1);
-                let data = self.read_reg(RT::HL).wrapping_add(1);
-                self.set_reg(RT::HL, data);
+                self.fetched_data = self.read_cycle(bus, address) as u16;
+                self.set_reg(RT::HL, address.wrapping_add(1));
             }
             AM::RegHLD => {
                 let address = self.read_reg(self.cur_inst.r2);
-                self.fetched_data = bus.read(address) as u16;
-                bus.cycle(1);
-                let data = self.read_reg(RT::HL).wrapping_sub(1);
-                self.set_reg(RT::HL, data);
+                self.fetched_data = self.read_cycle(bus, address) as u16;
+                self.set_reg(RT::HL, address.wrapping_sub(1));
             }
             AM::HLIReg => {
                 self.fetched_data = self.read_reg(self.cur_inst.r2);
                 self.mem_dest = self.read_reg(self.cur_inst.r1);
 
                 self.dest_is_mem = true;
-                self.set_reg(RT::HL, self.read_reg(RT::HL) + 1);
+                self.set_reg(RT::HL, self.mem_dest.wrapping_add(1));
             }
             AM::HLDReg => {
                 self.fetched_data = self.read_reg(self.cur_inst.r2);
                 self.mem_dest = self.read_reg(self.cur_inst.r1);
 
                 self.dest_is_mem = true;
-                self.set_reg(RT::HL, self.read_reg(RT::HL) - 1);
-            }
-            AM::RegA8 => {
-                self.fetched_data = bus.read(self.regs.pc) as u16;
-                bus.cycle(1);
-                self.regs.pc += 1;
+                self.set_reg(RT::HL, self.mem_dest.wrapping_sub(1));
             }
             AM::A8Reg => {
-                self.mem_dest = bus.read(self.regs.pc) as u16 | 0xFF00;
+                self.mem_dest = self.fetch_byte(bus) as u16 | 0xFF00;
                 self.dest_is_mem = true;
-                bus.cycle(1);
-                self.regs.pc += 1;
-            }
-            AM::HLRegSPReg => {
-                self.fetched_data = bus.read(self.regs.pc) as u16;
-                bus.cycle(1);
-                self.regs.pc += 1;
-            }
-            AM::D8 => {
-                self.fetched_data = bus.read(self.regs.pc) as u16;
-                bus.cycle(1);
-                self.regs.pc += 1;
             }
             AM::A16Reg | AM::D16Reg => {
-                let lo = bus.read(self.regs.pc);
-                bus.cycle(1);
-                let hi = bus.read(self.regs.pc + 1);
-                bus.cycle(1);
-
-                self.mem_dest = bytes_to_word!(lo, hi);
+                self.mem_dest = self.fetch_word(bus);
                 self.dest_is_mem = true;
-
-                self.regs.pc += 2;
                 self.fetched_data = self.read_reg(self.cur_inst.r2);
             }
             AM::MemD8 => {
-                self.fetched_data = bus.read(self.regs.pc) as u16;
-                bus.cycle(1);
-                self.regs.pc += 1;
+                self.fetched_data = self.fetch_byte(bus) as u16;
                 self.mem_dest = self.read_reg(self.cur_inst.r1);
                 self.dest_is_mem = true;
             }
             AM::Mem => {
                 self.mem_dest = self.read_reg(self.cur_inst.r1);
                 self.dest_is_mem = true;
-                let reg_1 = self.read_reg(self.cur_inst.r1);
-
-                self.fetched_data = bus.read(reg_1) as u16;
-                bus.cycle(1);
+                self.fetched_data = self.read_cycle(bus, self.mem_dest) as u16;
             }
             AM::RegA16 => {
-                let lo = bus.read(self.regs.pc);
-                bus.cycle(1);
-                let hi = bus.read(self.regs.pc + 1);
-                bus.cycle(1);
-
-                let addr = bytes_to_word!(lo, hi);
-
-                self.regs.pc += 2;
-                self.fetched_data = bus.read(addr) as u16;
-                bus.cycle(1);
-            } //_ => panic!("Unknown adressing mode: {:?}", self.cur_inst.mode),
+                let address = self.fetch_word(bus);
+                self.fetched_data = self.read_cycle(bus, address) as u16;
+            }
         };
     }
 
     pub fn stack_push(&mut self, data: u8, bus: &mut Bus) {
         self.regs.sp = self.regs.sp.wrapping_sub(1);
-        bus.write(self.regs.sp, data);
+        self.write_cycle(bus, self.regs.sp, data);
     }
 
     pub fn stack_push16(&mut self, data: u16, bus: &mut Bus) {
@@ -259,8 +266,8 @@ impl Cpu {
         self.stack_push((data & 0xFF) as u8, bus);
     }
 
-    fn stack_pop(&mut self, bus: &Bus) -> u8 {
-        let res = bus.read(self.regs.sp);
+    fn stack_pop(&mut self, bus: &mut Bus) -> u8 {
+        let res = self.read_cycle(bus, self.regs.sp);
         self.regs.sp = self.regs.sp.wrapping_add(1);
 
         res
@@ -1,11 +1,29 @@
 // Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
 // SPDX-License-Identifier: gpl-3.0-only
 
+/// Illegal opcode that hung the CPU
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CpuLock {
+    pub pc: u16,
+    pub opcode: u8,
+}
+
+impl std::fmt::Display for CpuLock {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "CPU locked at PC={:04X} (opcode {:02X})",
+            self.pc, self.opcode
+        )
+    }
+}
+
 #[derive(Debug)]
 pub struct Emu {
     pub die: bool,
     pub paused: bool,
     pub running: bool,
+    pub cpu_lock: Option<CpuLock>,
 }
 
 impl Emu {
@@ -14,6 +32,7 @@ impl Emu {
             die: false,
             paused: false,
             running: false,
+            cpu_lock: None,
         }
     }
 }
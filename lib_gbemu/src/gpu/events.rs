@@ -0,0 +1,112 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Per-frame record of PPU-relevant events with the LY and dot they happened on,
+// for raster-effect debugging. Recording is off unless a debugger asks for it.
+//
+
+use super::ppu::{LINES_PER_FRAME, TICKS_PER_LINE};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// New STAT mode, 0..=3
+    Mode(u8),
+    /// IF bit requested through `InterruptState::enable_flag`
+    Interrupt(u8),
+    IoWrite {
+        address: u16,
+        value: u8,
+    },
+    /// OAM DMA started from `source` << 8
+    DmaStart(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventFilter {
+    Mode = 0x01,
+    Interrupt = 0x02,
+    IoWrite = 0x04,
+    DmaStart = 0x08,
+}
+
+impl EventFilter {
+    pub const ALL: u8 =
+        Self::Mode as u8 | Self::Interrupt as u8 | Self::IoWrite as u8 | Self::DmaStart as u8;
+}
+
+impl EventKind {
+    pub fn filter(&self) -> EventFilter {
+        match self {
+            EventKind::Mode(_) => EventFilter::Mode,
+            EventKind::Interrupt(_) => EventFilter::Interrupt,
+            EventKind::IoWrite { .. } => EventFilter::IoWrite,
+            EventKind::DmaStart(_) => EventFilter::DmaStart,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PpuEvent {
+    pub ly: u8,
+    pub dot: u16,
+    pub kind: EventKind,
+}
+
+#[derive(Debug)]
+pub struct EventRecorder {
+    pub enabled: bool,
+    /// Events of the last complete frame, in the order they happened
+    pub frame: Vec<PpuEvent>,
+    current: Vec<PpuEvent>,
+    last_ly: u8,
+}
+
+impl EventRecorder {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            frame: Vec::new(),
+            current: Vec::new(),
+            last_ly: 0,
+        }
+    }
+
+    pub fn record(&mut self, ly: u8, dot: u32, kind: EventKind) {
+        if !self.enabled {
+            return;
+        }
+
+        self.current.push(PpuEvent {
+            ly: ly.min(LINES_PER_FRAME as u8 - 1),
+            dot: dot.min(TICKS_PER_LINE - 1) as u16,
+            kind,
+        });
+    }
+
+    /// Called every dot, a new frame starts when LY wraps to 0
+    pub fn set_line(&mut self, ly: u8) {
+        if ly == 0 && self.last_ly != 0 {
+            self.end_frame();
+        }
+
+        self.last_ly = ly;
+    }
+
+    /// Frame boundary without LY moving, the blank frames of a disabled LCD
+    pub fn end_frame(&mut self) {
+        std::mem::swap(&mut self.frame, &mut self.current);
+        self.current.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.frame.clear();
+        self.current.clear();
+    }
+}
+
+impl Default for EventRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
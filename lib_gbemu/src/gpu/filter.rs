@@ -0,0 +1,208 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Optional post-processing of finished frames before they reach GbWindow.
+// Everything here runs on the CPU.
+//
+
+use super::{Color, X_RES, Y_RES};
+
+const INPUT_GAMMA: f32 = 2.2;
+const OUTPUT_GAMMA: f32 = 2.2;
+
+#[inline(always)]
+fn unpack(color: Color) -> (u32, u32, u32) {
+    ((color >> 16) & 0xFF, (color >> 8) & 0xFF, color & 0xFF)
+}
+
+#[inline(always)]
+fn pack(r: u32, g: u32, b: u32) -> Color {
+    0xFF000000 | (r << 16) | (g << 8) | b
+}
+
+/// `weight` of 0..=256 is the share of `b` in the result
+#[inline(always)]
+fn mix(a: Color, b: Color, weight: u32) -> Color {
+    let (ar, ag, ab) = unpack(a);
+    let (br, bg, bb) = unpack(b);
+    let lerp = |x: u32, y: u32| (x * (256 - weight) + y * weight) >> 8;
+
+    pack(lerp(ar, br), lerp(ag, bg), lerp(ab, bb))
+}
+
+/// Emulates the slow DMG LCD response: every frame keeps part of the previous one,
+/// so sprites flickered at 30 Hz look semi-transparent instead of blinking
+#[derive(Debug)]
+pub struct FrameBlender {
+    previous: Vec<Color>,
+    /// Share of the previous frame, 0..=256
+    pub persistence: u32,
+}
+
+impl FrameBlender {
+    pub fn new(persistence: u32) -> Self {
+        Self {
+            previous: Vec::new(),
+            persistence: persistence.min(256),
+        }
+    }
+
+    pub fn apply(&mut self, frame: &mut [Color]) {
+        if self.previous.len() != frame.len() {
+            self.previous = frame.to_vec();
+            return;
+        }
+
+        for (current, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+            *current = mix(*current, *previous, self.persistence);
+            *previous = *current;
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+}
+
+impl Default for FrameBlender {
+    fn default() -> Self {
+        Self::new(128)
+    }
+}
+
+/// CGB LCD color correction: gamma plus the channel bleed of the real screen
+#[derive(Debug)]
+pub struct ColorCorrection {
+    to_linear: [f32; 256],
+}
+
+impl ColorCorrection {
+    pub fn new() -> Self {
+        let mut to_linear = [0.0; 256];
+        for (value, linear) in to_linear.iter_mut().enumerate() {
+            *linear = (value as f32 / 255.0).powf(INPUT_GAMMA);
+        }
+
+        Self { to_linear }
+    }
+
+    pub fn apply(&self, frame: &mut [Color]) {
+        for color in frame.iter_mut() {
+            let (r, g, b) = unpack(*color);
+            let (r, g, b) = (
+                self.to_linear[r as usize],
+                self.to_linear[g as usize],
+                self.to_linear[b as usize],
+            );
+
+            // channel weights out of 32, each row sums to 32 so white stays white
+            let cr = (26.0 * r + 4.0 * g + 2.0 * b) / 32.0;
+            let cg = (24.0 * g + 8.0 * b) / 32.0;
+            let cb = (6.0 * r + 4.0 * g + 22.0 * b) / 32.0;
+
+            let encode = |value: f32| (value.powf(1.0 / OUTPUT_GAMMA) * 255.0).round() as u32;
+
+            *color = pack(encode(cr), encode(cg), encode(cb));
+        }
+    }
+}
+
+impl Default for ColorCorrection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Upscales `frame` by `scale` with darkened gaps between the LCD dots
+pub fn dot_matrix(
+    frame: &[Color],
+    width: usize,
+    height: usize,
+    scale: usize,
+    out: &mut Vec<Color>,
+) {
+    let out_width = width * scale;
+
+    out.clear();
+    out.resize(out_width * height * scale, 0);
+
+    for y in 0..height * scale {
+        for x in 0..out_width {
+            let color = frame[(x / scale) + (y / scale) * width];
+            let is_gap = scale > 1 && (x % scale == scale - 1 || y % scale == scale - 1);
+
+            out[x + y * out_width] = if is_gap {
+                mix(color, 0xFF000000, 64)
+            } else {
+                color
+            };
+        }
+    }
+}
+
+/// Post-processing chain: blending, color correction, then the dot-matrix look
+#[derive(Debug)]
+pub struct PostProcess {
+    pub blending: bool,
+    pub color_correction: bool,
+    pub dot_matrix: bool,
+    pub dot_matrix_scale: usize,
+
+    blender: FrameBlender,
+    correction: ColorCorrection,
+    frame: Vec<Color>,
+    scaled: Vec<Color>,
+}
+
+impl PostProcess {
+    pub fn new() -> Self {
+        Self {
+            blending: false,
+            color_correction: false,
+            dot_matrix: false,
+            dot_matrix_scale: 3,
+
+            blender: FrameBlender::default(),
+            correction: ColorCorrection::new(),
+            frame: Vec::with_capacity((X_RES * Y_RES) as usize),
+            scaled: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.blending || self.color_correction || self.dot_matrix
+    }
+
+    /// Returns the processed frame with its width and height
+    pub fn apply(&mut self, buffer: &[Color]) -> (&[Color], usize, usize) {
+        let (width, height) = (X_RES as usize, Y_RES as usize);
+
+        self.frame.clear();
+        self.frame.extend_from_slice(buffer);
+
+        if self.blending {
+            self.blender.apply(&mut self.frame);
+        } else {
+            self.blender.reset();
+        }
+
+        if self.color_correction {
+            self.correction.apply(&mut self.frame);
+        }
+
+        if self.dot_matrix {
+            let scale = self.dot_matrix_scale.max(1);
+            dot_matrix(&self.frame, width, height, scale, &mut self.scaled);
+            return (&self.scaled, width * scale, height * scale);
+        }
+
+        (&self.frame, width, height)
+    }
+}
+
+impl Default for PostProcess {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Read-only views of VRAM and OAM for debuggers: tile data, tile maps and
+// sprites rendered into plain color images.
+//
+
+use super::{png, ppu::Ppu, Color};
+
+pub const TILES_PER_ROW: usize = 16;
+pub const TILE_COUNT: usize = 384;
+pub const MAP_SIZE: usize = 256;
+pub const SPRITES_PER_ROW: usize = 8;
+
+// light and dark squares behind transparent sprite pixels
+const TRANSPARENT: [Color; 2] = [0xFFC8C8C8, 0xFF8C8C8C];
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Color>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0xFF000000; width * height],
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[x + y * self.width]
+    }
+
+    /// Pixels outside the image are ignored
+    #[inline(always)]
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        if x < self.width && y < self.height {
+            self.pixels[x + y * self.width] = color;
+        }
+    }
+
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    /// One pixel wide outline, wraps around the edges like the BG map does
+    pub fn wrapped_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Color) {
+        for i in 0..width {
+            let px = (x + i) % self.width;
+            self.set(px, y % self.height, color);
+            self.set(px, (y + height - 1) % self.height, color);
+        }
+
+        for i in 0..height {
+            let py = (y + i) % self.height;
+            self.set(x % self.width, py, color);
+            self.set((x + width - 1) % self.width, py, color);
+        }
+    }
+
+    /// Copies `src` to (x, y), every source pixel becomes a `scale` x `scale` square
+    pub fn blit(&mut self, src: &Image, x: usize, y: usize, scale: usize) {
+        self.blit_stretched(src, x, y, scale, scale);
+    }
+
+    /// Copies `src` to (x, y) with separate horizontal and vertical scale
+    pub fn blit_stretched(
+        &mut self,
+        src: &Image,
+        x: usize,
+        y: usize,
+        scale_x: usize,
+        scale_y: usize,
+    ) {
+        for sy in 0..src.height * scale_y {
+            for sx in 0..src.width * scale_x {
+                self.set(x + sx, y + sy, src.get(sx / scale_x, sy / scale_y));
+            }
+        }
+    }
+}
+
+/// Color indices of one row of the tile at `address`
+pub fn tile_row(ppu: &Ppu, address: u16, row: u16) -> [u8; 8] {
+    let lo = ppu.vram_read(address + row * 2);
+    let hi = ppu.vram_read(address + row * 2 + 1);
+    let mut pixels = [0; 8];
+
+    for (x, pixel) in pixels.iter_mut().enumerate() {
+        let bit = 7 - x;
+        *pixel = (((hi >> bit) & 1) << 1) | ((lo >> bit) & 1);
+    }
+
+    pixels
+}
+
+/// All 384 tiles from 0x8000, 16 per row, drawn with the raw shades
+pub fn tile_data(ppu: &Ppu) -> Image {
+    let rows = TILE_COUNT / TILES_PER_ROW;
+    let mut image = Image::new(TILES_PER_ROW * 8, rows * 8);
+
+    for tile in 0..TILE_COUNT {
+        let address = 0x8000 + tile as u16 * 16;
+        let (tile_x, tile_y) = ((tile % TILES_PER_ROW) * 8, (tile / TILES_PER_ROW) * 8);
+
+        for row in 0..8 {
+            for (x, color) in tile_row(ppu, address, row).iter().enumerate() {
+                image.set(
+                    tile_x + x,
+                    tile_y + row as usize,
+                    ppu.lcd.shades[*color as usize],
+                );
+            }
+        }
+    }
+
+    image
+}
+
+/// Full 256x256 tile map at `map_area`, using the current tile data area and BGP
+pub fn tile_map(ppu: &Ppu, map_area: u16) -> Image {
+    let mut image = Image::new(MAP_SIZE, MAP_SIZE);
+    let data_area = ppu.lcd.bgw_data_area();
+
+    for map_y in 0..32u16 {
+        for map_x in 0..32u16 {
+            let mut tile = ppu.vram_read(map_area + map_x + map_y * 32);
+            if data_area == 0x8800 {
+                tile = tile.wrapping_add(128);
+            }
+            let address = data_area + tile as u16 * 16;
+
+            for row in 0..8 {
+                for (x, color) in tile_row(ppu, address, row).iter().enumerate() {
+                    image.set(
+                        map_x as usize * 8 + x,
+                        (map_y * 8 + row) as usize,
+                        ppu.lcd.bg_colors[*color as usize],
+                    );
+                }
+            }
+        }
+    }
+
+    image
+}
+
+/// OAM entry `index` drawn with its palette and flips at the current sprite height,
+/// transparent pixels show a checkerboard
+pub fn sprite(ppu: &Ppu, index: usize) -> Image {
+    let height = ppu.lcd.obj_height() as usize;
+    let mut image = Image::new(8, height);
+
+    draw_sprite(ppu, index, &mut image, 0, 0, |x, y| {
+        TRANSPARENT[(x / 2 + y / 2) % 2]
+    });
+
+    image
+}
+
+/// All 40 OAM sprites in OAM order, 8 per row with transparent background
+pub fn sprite_sheet(ppu: &Ppu) -> Image {
+    let height = ppu.lcd.obj_height() as usize;
+    let rows = ppu.oam_ram.len() / SPRITES_PER_ROW;
+    let mut image = Image::new(SPRITES_PER_ROW * 8, rows * height);
+    image.pixels.fill(0);
+
+    for index in 0..ppu.oam_ram.len() {
+        let (x, y) = (
+            (index % SPRITES_PER_ROW) * 8,
+            (index / SPRITES_PER_ROW) * height,
+        );
+        draw_sprite(ppu, index, &mut image, x, y, |_, _| 0);
+    }
+
+    image
+}
+
+fn draw_sprite<F>(
+    ppu: &Ppu,
+    index: usize,
+    image: &mut Image,
+    left: usize,
+    top: usize,
+    transparent: F,
+) where
+    F: Fn(usize, usize) -> Color,
+{
+    let oam = &ppu.oam_ram[index];
+    let height = ppu.lcd.obj_height() as u16;
+
+    let tile = if height == 16 {
+        oam.tile() & !1
+    } else {
+        oam.tile()
+    };
+    let colors = if oam.f_pn() {
+        ppu.lcd.sp2_colors
+    } else {
+        ppu.lcd.sp1_colors
+    };
+
+    for y in 0..height {
+        let row = if oam.f_y_flip() { height - 1 - y } else { y };
+        let pixels = tile_row(ppu, 0x8000 + tile as u16 * 16, row);
+
+        for x in 0..8 {
+            let color = pixels[if oam.f_x_flip() { 7 - x } else { x }];
+            let color = if color == 0 {
+                transparent(x, y as usize)
+            } else {
+                colors[color as usize]
+            };
+            image.set(left + x, top + y as usize, color);
+        }
+    }
+}
+
+pub fn save_tile_data(ppu: &Ppu, path: &str) -> Result<(), &'static str> {
+    png::save(&tile_data(ppu), path)
+}
+
+/// `map_area` is 0x9800 or 0x9C00
+pub fn save_tile_map(ppu: &Ppu, map_area: u16, path: &str) -> Result<(), &'static str> {
+    if map_area != 0x9800 && map_area != 0x9C00 {
+        return Err("tile map must be at 0x9800 or 0x9C00");
+    }
+
+    png::save(&tile_map(ppu, map_area), path)
+}
+
+pub fn save_sprites(ppu: &Ppu, path: &str) -> Result<(), &'static str> {
+    png::save(&sprite_sheet(ppu), path)
+}
@@ -1,9 +1,7 @@
 // Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
 // SPDX-License-Identifier: gpl-3.0-only
 
-use super::{Color, LcdMode, StatInterruptSource};
-
-const PALLETTE_COLORS: [Color; 4] = super::DEFAULT_COLORS;
+use super::{palette::Shades, Color, LcdMode, StatInterruptSource};
 
 #[derive(Debug)]
 #[repr(C)]
@@ -17,12 +15,15 @@ pub struct Lcd {
     pub dma: u8,              // FF46
     pub bg_palette: u8,       // FF47
     pub obj_palette: [u8; 2], // FF48 FF49
-    pub win_x: u8,            // FF4A
-    pub win_y: u8,            // FF4B
+    pub win_y: u8,            // FF4A
+    pub win_x: u8,            // FF4B
 
     pub bg_colors: [Color; 4],
     pub sp1_colors: [Color; 4],
     pub sp2_colors: [Color; 4],
+
+    // user palette the registers above select from
+    pub shades: Shades,
 }
 
 #[repr(u8)]
@@ -44,13 +45,15 @@ impl Lcd {
             dma: 0,
             bg_palette: 0xFC,
             obj_palette: [0xFF; 2],
-            win_x: 0,
             win_y: 0,
-            bg_colors: PALLETTE_COLORS,
-            sp1_colors: PALLETTE_COLORS,
-            sp2_colors: PALLETTE_COLORS,
+            win_x: 0,
+            bg_colors: super::DEFAULT_COLORS,
+            sp1_colors: super::DEFAULT_COLORS,
+            sp2_colors: super::DEFAULT_COLORS,
+            shades: super::DEFAULT_COLORS,
         };
         lcd.set_lcds_mode(LcdMode::Oam);
+        lcd.set_shades(super::DEFAULT_COLORS);
         lcd
     }
 
@@ -67,12 +70,15 @@ impl Lcd {
     pub fn write(&mut self, address: u16, value: u8) {
         let offset = address.wrapping_sub(0xFF40);
 
+        let lcds = self.lcds;
         unsafe {
             let ptr = self as *mut _ as *mut u8;
             *ptr.add(offset as usize) = value;
         }
 
         match address {
+            // mode and LYC flag are read-only
+            0xFF41 => self.lcds = (value & !0b0000_0111) | (lcds & 0b0000_0111),
             0xFF47 => self.set_pallete(value, Pallete::BgColors),
             0xFF48 => self.set_pallete(value & 0b1111_1100, Pallete::Sp1),
             0xFF49 => self.set_pallete(value & 0b1111_1100, Pallete::Sp2),
@@ -88,6 +94,27 @@ impl Lcd {
         self.lcds = (self.lcds & 0b1111_1011) | (value << 2);
     }
 
+    pub fn compare_ly(&mut self) {
+        self.set_lyc((self.ly == self.ly_compare) as u8);
+    }
+
+    /// State of the shared STAT interrupt line for the given set of enabled sources
+    pub fn stat_line(&self, sources: u8) -> bool {
+        let mode_source = match self.get_lcds_mode() {
+            LcdMode::HBlank => StatInterruptSource::HBlank as u8,
+            LcdMode::VBlank => StatInterruptSource::VBlank as u8,
+            LcdMode::Oam => StatInterruptSource::Oam as u8,
+            LcdMode::Xfer => 0,
+        };
+        let lyc_source = if self.lyc() != 0 {
+            StatInterruptSource::Lyc as u8
+        } else {
+            0
+        };
+
+        (mode_source | lyc_source) & sources != 0
+    }
+
     pub fn is_bgw_enabled(&self) -> u8 {
         self.lcdc & 0b0000_0001
     }
@@ -96,8 +123,9 @@ impl Lcd {
         self.lcdc & 0b0000_0010
     }
 
+    /// WY is latched by the PPU per frame, only LCDC and WX are checked here
     pub fn is_window_visible(&self) -> bool {
-        self.is_window_enabled() != 0 && self.win_x <= 166 && self.win_y < super::Y_RES as u8
+        self.is_window_enabled() != 0 && self.win_x <= 166
     }
 
     pub fn is_window_enabled(&self) -> u8 {
@@ -158,17 +186,26 @@ impl Lcd {
         self.lcds = (self.lcds & 0b1111_1100) | (mode as u8);
     }
 
+    /// Switches the user palette, colors of all three palette registers are recalculated
+    pub fn set_shades(&mut self, shades: Shades) {
+        self.shades = shades;
+
+        self.set_pallete(self.bg_palette, Pallete::BgColors);
+        self.set_pallete(self.obj_palette[0] & 0b1111_1100, Pallete::Sp1);
+        self.set_pallete(self.obj_palette[1] & 0b1111_1100, Pallete::Sp2);
+    }
+
     fn set_pallete(&mut self, data: u8, palette: Pallete) {
-        self.bg_colors = match palette {
-            Pallete::Sp1 => self.sp1_colors,
-            Pallete::Sp2 => self.sp2_colors,
-            Pallete::BgColors => self.bg_colors,
+        let colors = match palette {
+            Pallete::BgColors => &mut self.bg_colors,
+            Pallete::Sp1 => &mut self.sp1_colors,
+            Pallete::Sp2 => &mut self.sp2_colors,
         };
 
-        self.bg_colors[0] = PALLETTE_COLORS[(data & 0b11) as usize];
-        self.bg_colors[1] = PALLETTE_COLORS[((data >> 2) & 0b11) as usize];
-        self.bg_colors[2] = PALLETTE_COLORS[((data >> 4) & 0b11) as usize];
-        self.bg_colors[3] = PALLETTE_COLORS[((data >> 6) & 0b11) as usize];
+        colors[0] = self.shades[(data & 0b11) as usize];
+        colors[1] = self.shades[((data >> 2) & 0b11) as usize];
+        colors[2] = self.shades[((data >> 4) & 0b11) as usize];
+        colors[3] = self.shades[((data >> 6) & 0b11) as usize];
     }
 }
 
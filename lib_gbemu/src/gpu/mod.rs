@@ -3,10 +3,16 @@
 
 pub mod ppu;
 pub mod lcd;
+pub mod palette;
+pub mod filter;
+pub mod scaler;
+pub mod inspector;
+pub mod png;
+pub mod events;
 
 pub type Color = u32;
 
-const DEFAULT_COLORS: [Color; 4] = [0xFFFFFFFF, 0xFFAAAAAA, 0xFF555555, 0xFF000000];
+const DEFAULT_COLORS: palette::Shades = palette::PalettePreset::Grayscale.shades();
 pub const X_RES: i32 = 160;
 pub const Y_RES: i32 = 144;
 
@@ -25,7 +31,20 @@ pub enum StatInterruptSource {
     Lyc = (1 << 6),
 }
 
+impl StatInterruptSource {
+    pub const ALL: u8 = Self::HBlank as u8 | Self::VBlank as u8 | Self::Oam as u8 | Self::Lyc as u8;
+}
+
 pub trait GbWindow {
     fn draw_frame(&mut self, buffer: &[Color]);
     fn present(&mut self);
+}
+
+/// Drops every frame, for headless runs and tests
+pub struct NullWindow;
+
+impl GbWindow for NullWindow {
+    fn draw_frame(&mut self, _buffer: &[Color]) {}
+
+    fn present(&mut self) {}
 }
\ No newline at end of file
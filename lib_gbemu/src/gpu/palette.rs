@@ -0,0 +1,119 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// 4-shade user palettes, the DMG palette registers pick shades from them.
+// Custom palette files hold exactly four colors, lightest first, written as
+// #RRGGBB or 0xRRGGBB and separated by whitespace, commas or new lines, so both
+//
+//     ; my palette
+//     #E0F8D0 #88C070 #346856 #081820
+//
+// and a JSON file like { "colors": ["#E0F8D0", "#88C070", "#346856", "#081820"] }
+// are accepted. Lines starting with ';' are comments.
+//
+
+use super::Color;
+
+use std::fs;
+
+pub type Shades = [Color; 4];
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PalettePreset {
+    #[default]
+    Grayscale,
+    DmgGreen,
+    Pocket,
+    Light,
+}
+
+impl PalettePreset {
+    pub const ALL: [PalettePreset; 4] = [
+        PalettePreset::Grayscale,
+        PalettePreset::DmgGreen,
+        PalettePreset::Pocket,
+        PalettePreset::Light,
+    ];
+
+    pub const fn shades(self) -> Shades {
+        match self {
+            PalettePreset::Grayscale => [0xFFFFFFFF, 0xFFAAAAAA, 0xFF555555, 0xFF000000],
+            PalettePreset::DmgGreen => [0xFF9BBC0F, 0xFF8BAC0F, 0xFF306230, 0xFF0F380F],
+            PalettePreset::Pocket => [0xFFC4CFA1, 0xFF8B956D, 0xFF4D533C, 0xFF1F1F1F],
+            PalettePreset::Light => [0xFF00B581, 0xFF009A71, 0xFF00694A, 0xFF004F3B],
+        }
+    }
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            PalettePreset::Grayscale => "grayscale",
+            PalettePreset::DmgGreen => "dmg",
+            PalettePreset::Pocket => "pocket",
+            PalettePreset::Light => "light",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|preset| *preset == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+pub fn load_palette(path: &str) -> Result<Shades, &'static str> {
+    let data = fs::read_to_string(path).map_err(|_| "cannot load palette")?;
+
+    parse_palette(&data)
+}
+
+pub fn parse_palette(data: &str) -> Result<Shades, &'static str> {
+    let mut shades: Shades = [0; 4];
+    let mut count = 0;
+
+    let tokens = data
+        .lines()
+        .filter(|line| !line.trim_start().starts_with(';'))
+        .flat_map(|line| line.split(|c: char| c.is_whitespace() || ",\"'[]{}:".contains(c)));
+
+    for token in tokens {
+        let hex = match token.strip_prefix('#') {
+            Some(hex) => hex,
+            None => match token
+                .strip_prefix("0x")
+                .or_else(|| token.strip_prefix("0X"))
+            {
+                Some(hex) => hex,
+                None => continue,
+            },
+        };
+
+        if hex.len() != 6 {
+            return Err("palette colors must be in RRGGBB format");
+        }
+
+        let rgb = u32::from_str_radix(hex, 16).map_err(|_| "invalid palette color")?;
+
+        if count >= shades.len() {
+            return Err("palette must contain exactly 4 colors");
+        }
+
+        shades[count] = 0xFF000000 | rgb;
+        count += 1;
+    }
+
+    if count != shades.len() {
+        return Err("palette must contain exactly 4 colors");
+    }
+
+    Ok(shades)
+}
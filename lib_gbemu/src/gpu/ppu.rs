@@ -2,14 +2,30 @@
 // SPDX-License-Identifier: gpl-3.0-only
 
 use crate::{
-    emu::Emu,
     gpu::{lcd::Lcd, Color, LcdMode, StatInterruptSource, X_RES, Y_RES},
-    memory::{interrupts::Interrupt, Bus},
+    memory::{
+        interrupts::{Interrupt, InterruptState},
+        Bus,
+    },
 };
 use std::collections::VecDeque;
 
-const LINES_PER_FRAME: u32 = 154;
-const TICKS_PER_LINE: u32 = 456;
+pub const LINES_PER_FRAME: u32 = 154;
+pub const TICKS_PER_LINE: u32 = 456;
+const TICKS_PER_FRAME: u32 = LINES_PER_FRAME * TICKS_PER_LINE;
+// first line after the LCD is switched on is 4 dots shorter
+const LCD_ON_SKIPPED_TICKS: u32 = 4;
+const MAX_SPRITES_PER_LINE: usize = 10;
+// false colors of the layer overlay, indexed by PixelSource and then by color index
+const OVERLAY_COLORS: [[Color; 4]; 4] = [
+    [0xFFC0C0FF, 0xFF8080FF, 0xFF4040C0, 0xFF000080], // background
+    [0xFFC0FFC0, 0xFF80E080, 0xFF40A040, 0xFF006000], // window
+    [0xFFFFC0C0, 0xFFFF8080, 0xFFC04040, 0xFF800000], // sprites, OBP0
+    [0xFFFFC0FF, 0xFFFF80FF, 0xFFC040C0, 0xFF800080], // sprites, OBP1
+];
+const OAM_TICKS: u32 = 80;
+// fixed mode 3 length used by the scanline renderer
+const SCANLINE_XFER_TICKS: u32 = 172;
 const FRAME_BUFFER_SIZE: usize = (X_RES * Y_RES) as usize;
 const DEBUG: bool = false;
 
@@ -22,22 +38,60 @@ pub enum FetchState {
     Push,
 }
 
+/// `Fifo` renders dot by dot and reproduces mid-line effects,
+/// `Scanline` draws the whole line at the end of mode 3 and is faster
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Renderer {
+    #[default]
+    Fifo,
+    Scanline,
+}
+
+/// Runtime switches for debugging and screenshots, they do not exist on hardware
+#[derive(Debug, Clone, Copy)]
+pub struct LayerOptions {
+    pub background: bool,
+    pub window: bool,
+    pub sprites: bool,
+    /// Hardware limit of 10 sprites per line, lifting it removes flicker in multiplexed games
+    pub sprite_limit: bool,
+    /// Draws every pixel in a false color of the layer that produced it
+    pub overlay: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PixelSource {
+    Background,
+    Window,
+    Sprite0,
+    Sprite1,
+}
+
+/// Pixel as it travels through the FIFO, the palette is applied only on output
+#[derive(Clone, Copy, Debug)]
+pub struct FifoPixel {
+    pub color: u8,
+    pub source: PixelSource,
+}
+
 #[derive(Debug)]
 pub struct PixelFiFo {
     current_fetch_state: FetchState,
-    fifo: VecDeque<Color>,
-    line_x: u8,
+    fifo: VecDeque<FifoPixel>,
+    // pixels dropped before reaching the screen (SCX fine scroll, WX < 7)
+    discard: u8,
     pushed_x: u8,
     fetch_x: u8,
     bgw_fetch_data: [u8; 3],
-    fetch_entry_data: [u8; 6],
     map_y: u8,
     map_x: u8,
     tile_y: u8,
-    fifo_x: u8,
+    window_active: bool,
+    window_started: bool,
 }
 
 #[derive(Clone, Copy, Debug)]
+#[repr(C)]
 pub struct Oam {
     y: u8,
     x: u8,
@@ -45,16 +99,40 @@ pub struct Oam {
     flags: u8,
 }
 
+/// Sprite selected during the OAM scan, with its tile row for the current line
+#[derive(Clone, Copy, Debug)]
+struct LineSprite {
+    oam: Oam,
+    data_lo: u8,
+    data_hi: u8,
+}
+
 #[derive(Debug)]
 pub struct Ppu {
     pub oam_ram: [Oam; 40],
     pub vram: [u8; 0x2000],
 
-    line_sprites: VecDeque<Oam>,
-
-    fetched_entry_count: u8,
-    fetched_entries: [Oam; 3],
+    line_sprites: Vec<LineSprite>,
+    // internal window line counter, only advances on lines where the window was drawn
     pub window_line: u8,
+    // WY latch, set once LY == WY and kept until the end of the frame
+    window_y_triggered: bool,
+    // WX = 166 makes the window cover the whole next line
+    window_wx166_glitch: bool,
+
+    // all enabled STAT sources ORed together, interrupt fires on rising edge
+    stat_line: bool,
+
+    lcd_on: bool,
+    lcd_off_ticks: u32,
+    // frozen by STOP, the screen is blank until the CPU wakes up
+    stopped: bool,
+    /// Block CPU access to VRAM/OAM while the PPU is using them, disable for debugging
+    pub memory_locking: bool,
+    pub renderer: Renderer,
+    pub layers: LayerOptions,
+    // the first frame after the LCD is switched on is not shown
+    skip_frame: bool,
 
     pub pfc: PixelFiFo,
 
@@ -77,10 +155,20 @@ impl Ppu {
 
             pfc: PixelFiFo::new(),
 
-            fetched_entry_count: 0,
-            line_sprites: VecDeque::new(),
-            fetched_entries: [Oam::new(); 3],
+            line_sprites: Vec::with_capacity(MAX_SPRITES_PER_LINE),
             window_line: 0,
+            window_y_triggered: false,
+            window_wx166_glitch: false,
+
+            stat_line: false,
+
+            lcd_on: true,
+            lcd_off_ticks: 0,
+            stopped: false,
+            memory_locking: true,
+            renderer: Renderer::Fifo,
+            layers: LayerOptions::new(),
+            skip_frame: false,
 
             lcd: Lcd::new(),
         }
@@ -93,7 +181,7 @@ impl Ppu {
                     format!(
                         "[{}]",
                         $vec.iter()
-                            .map(|v| format!("0x{:X}", v))
+                            .map(|v| format!("0x{:X}", v.color))
                             .collect::<Vec<String>>()
                             .join(", ")
                     )
@@ -119,46 +207,120 @@ impl Ppu {
             // print!("{debug_output}");
             crate::common::debug_write(debug_output);
         }
-        if bus.timer.ticks == 859831 {
-            println!("Break");
+
+        self.stopped = false;
+
+        if !self.lcd.is_lcd_enabled() {
+            self.tick_lcd_off(bus);
+            return;
+        }
+
+        if !self.lcd_on {
+            self.lcd_turn_on();
         }
 
         self.line_ticks += 1;
 
         match self.lcd.get_lcds_mode() {
             LcdMode::Oam => self.mode_oam(),
-            LcdMode::Xfer => self.mode_xfer(bus),
-            LcdMode::VBlank => self.mode_vblank(bus),
+            LcdMode::Xfer => self.mode_xfer(),
+            LcdMode::VBlank => self.mode_vblank(),
             LcdMode::HBlank => self.mode_hblank(bus),
         }
+
+        self.lcd.compare_ly();
+        self.update_stat_line(&mut bus.interrupts, self.lcd.lcds);
     }
 
-    // TODO: rewrite
-    pub fn draw_frame(&mut self, bus: &mut Bus) {
-        bus.screen.draw_frame(&self.video_buffer);
-        bus.screen.present();
+    fn tick_lcd_off(&mut self, bus: &mut Bus) {
+        if self.lcd_on {
+            // LY=LYC flag keeps its last value while the LCD is off
+            self.lcd_on = false;
+            self.lcd.ly = 0;
+            self.lcd.set_lcds_mode(LcdMode::HBlank);
+            self.line_ticks = 0;
+            self.window_line = 0;
+            self.window_y_triggered = false;
+            self.window_wx166_glitch = false;
+            self.stat_line = false;
+            self.pipeline_fifo_reset();
+
+            self.video_buffer.fill(self.lcd.shades[0]);
+            self.draw_frame(bus);
+            self.lcd_off_ticks = 0;
+        }
+
+        // keep delivering blank frames so the frontend stays in sync
+        self.lcd_off_ticks += 1;
+        if self.lcd_off_ticks >= TICKS_PER_FRAME {
+            self.lcd_off_ticks = 0;
+            self.current_frame += 1;
+            self.draw_frame(bus);
+        }
     }
 
-    pub fn increment_ly(&mut self) -> Option<Interrupt> {
-        if self.lcd.is_window_visible()
-            && self.lcd.ly >= self.lcd.win_y
-            && (self.lcd.ly as i32) < self.lcd.win_y as i32 + Y_RES
-        {
-            self.window_line = self.window_line.wrapping_add(1);
+    /// Dot while the CPU is in STOP: PPU state is kept as is, the frontend
+    /// gets blank frames at the normal rate
+    pub fn tick_stopped(&mut self, bus: &mut Bus) {
+        if !self.stopped {
+            self.stopped = true;
+            self.video_buffer.fill(self.lcd.shades[0]);
+            self.draw_frame(bus);
+            self.lcd_off_ticks = 0;
         }
-        self.lcd.ly += 1;
 
-        if self.lcd.ly == self.lcd.ly_compare {
-            self.lcd.set_lyc(1);
+        self.lcd_off_ticks += 1;
+        if self.lcd_off_ticks >= TICKS_PER_FRAME {
+            self.lcd_off_ticks = 0;
+            self.current_frame += 1;
+            self.draw_frame(bus);
+        }
+    }
 
-            if self.lcd.lcds & StatInterruptSource::Lyc as u8 != 0 {
-                return Some(Interrupt::LcdStat);
-            }
-        } else {
-            self.lcd.set_lyc(0);
+    fn lcd_turn_on(&mut self) {
+        self.lcd_on = true;
+        self.skip_frame = true;
+        self.line_ticks = LCD_ON_SKIPPED_TICKS;
+        self.enter_oam_mode();
+    }
+
+    fn enter_oam_mode(&mut self) {
+        self.lcd.set_lcds_mode(LcdMode::Oam);
+
+        // WY only latches while the window is enabled
+        if self.lcd.is_window_enabled() != 0 && self.lcd.ly == self.lcd.win_y {
+            self.window_y_triggered = true;
         }
+    }
 
-        None
+    /// DMG quirk: writing FF41 behaves as if every STAT source was enabled for one cycle,
+    /// only while the LCD is on
+    pub fn stat_write_bug(&mut self, interrupts: &mut InterruptState) {
+        if !self.lcd.is_lcd_enabled() {
+            return;
+        }
+
+        self.update_stat_line(interrupts, StatInterruptSource::ALL);
+    }
+
+    fn update_stat_line(&mut self, interrupts: &mut InterruptState, sources: u8) {
+        let line = self.lcd.stat_line(sources);
+
+        if line && !self.stat_line {
+            interrupts.enable_flag(Interrupt::LcdStat);
+        }
+
+        self.stat_line = line;
+    }
+
+    // TODO: rewrite
+    pub fn draw_frame(&mut self, bus: &mut Bus) {
+        bus.screen.draw_frame(&self.video_buffer);
+        bus.screen.present();
+    }
+
+    pub fn increment_ly(&mut self) {
+        self.lcd.ly += 1;
     }
 
     pub fn oam_write(&mut self, address: u16, value: u8) {
@@ -183,6 +345,46 @@ impl Ppu {
         }
     }
 
+    pub fn is_vram_locked(&self) -> bool {
+        self.memory_locking && matches!(self.lcd.get_lcds_mode(), LcdMode::Xfer)
+    }
+
+    pub fn is_oam_locked(&self) -> bool {
+        self.memory_locking && matches!(self.lcd.get_lcds_mode(), LcdMode::Oam | LcdMode::Xfer)
+    }
+
+    /// DMG OAM corruption bug, triggered when the CPU puts an OAM address on the bus
+    /// (16-bit INC/DEC) while the PPU is scanning OAM in mode 2
+    pub fn oam_bug_write(&mut self, address: u16) {
+        if !(0xFE00..=0xFEFF).contains(&address)
+            || !self.lcd_on
+            || !matches!(self.lcd.get_lcds_mode(), LcdMode::Oam)
+        {
+            return;
+        }
+
+        // OAM is scanned as 20 rows of 8 bytes, one row every 4 dots
+        let row = (self.line_ticks / 4) as usize;
+        if row == 0 || row >= 20 {
+            return;
+        }
+
+        let oam: &mut [u8; 160] = unsafe { &mut *(&mut self.oam_ram as *mut _ as *mut [u8; 160]) };
+        let word = |oam: &[u8; 160], index: usize| bytes_to_word!(oam[index], oam[index + 1]);
+
+        let cur = row * 8;
+        let prev = cur - 8;
+
+        let a = word(oam, cur);
+        let b = word(oam, prev);
+        let c = word(oam, prev + 4);
+        let value = ((a ^ c) & (b ^ c)) ^ c;
+
+        oam[cur] = value as u8;
+        oam[cur + 1] = (value >> 8) as u8;
+        oam.copy_within(prev + 2..prev + 8, cur + 2);
+    }
+
     pub fn vram_write(&mut self, address: u16, value: u8) {
         self.vram[(address - 0x8000) as usize] = value;
     }
@@ -192,124 +394,152 @@ impl Ppu {
     }
 
     fn load_line_sprites(&mut self) {
-        let current_y = self.lcd.ly;
-        let sprite_height = self.lcd.obj_height();
+        let current_y = self.lcd.ly as u16 + 16;
+        let sprite_height = self.lcd.obj_height() as u16;
 
-        for oam in self.oam_ram {
-            // if not visible
-            if oam.x == 0 {
-                continue;
-            }
+        self.line_sprites.clear();
 
-            // maxsimum is 10 sprite per line
-            if self.line_sprites.len() >= 10 {
+        // OAM scan order, sprites with X = 0 still count against the limit
+        for oam in self.oam_ram {
+            if self.layers.sprite_limit && self.line_sprites.len() >= MAX_SPRITES_PER_LINE {
                 break;
             }
 
-            // if sprite is on the current line
-            if oam.y <= current_y + 16 && oam.y + sprite_height > current_y + 16 {
-                self.line_sprites.push_back(oam);
-                // TODO: sorting
+            if oam.y as u16 <= current_y && oam.y as u16 + sprite_height > current_y {
+                let (data_lo, data_hi) = self.sprite_tile_row(&oam, current_y - oam.y as u16);
+                self.line_sprites.push(LineSprite {
+                    oam,
+                    data_lo,
+                    data_hi,
+                });
             }
         }
+
+        // DMG priority: smaller X first, equal X keeps OAM order (stable sort)
+        self.line_sprites.sort_by_key(|sprite| sprite.oam.x);
     }
 
-    fn fetch_sprite_pixels(&mut self, bg_color: u8) -> Option<Color> {
-        let mut result: Option<Color> = None;
-        for i in 0..(self.fetched_entry_count as usize) {
-            let sp_x = self.fetched_entries[i].x - 8 + (self.lcd.scroll_x % 8);
+    fn sprite_tile_row(&self, oam: &Oam, row: u16) -> (u8, u8) {
+        let sprite_height = self.lcd.obj_height() as u16;
+        let mut tile_index = oam.tile;
+        let mut row = row;
 
-            if sp_x + 8 < self.pfc.fifo_x {
-                // Passed pixel point already
-                continue;
-            }
+        if sprite_height == 16 {
+            tile_index &= !1;
+        }
+
+        if oam.f_y_flip() {
+            row = sprite_height - 1 - row;
+        }
+
+        let address = 0x8000 + (tile_index as u16 * 16) + row * 2;
+
+        (self.vram_read(address), self.vram_read(address + 1))
+    }
 
-            let offset = (self.pfc.fifo_x - sp_x) as i32;
+    /// Sprite pixel at screen position `x`, `bg_color` is the BG/window color index below it
+    fn fetch_sprite_pixels(&self, x: i32, bg_color: u8) -> Option<FifoPixel> {
+        for sprite in self.line_sprites.iter() {
+            let offset = x - (sprite.oam.x as i32 - 8);
 
             if !(0..=7).contains(&offset) {
-                // out of bounds
                 continue;
             }
 
-            let mut bit = 7 - offset;
-
-            if self.fetched_entries[i].f_x_flip() {
-                bit = offset;
-            }
-            let hi = (((self.pfc.fetch_entry_data[i * 2] & (1 << bit)) != 0) as u8) << 1;
-            let lo = ((self.pfc.fetch_entry_data[(i * 2) + 1] & (1 << bit)) != 0) as u8;
+            let bit = if sprite.oam.f_x_flip() {
+                offset
+            } else {
+                7 - offset
+            };
 
-            let bg_priority = self.fetched_entries[i].f_bgp();
+            let lo = ((sprite.data_lo & (1 << bit)) != 0) as u8;
+            let hi = (((sprite.data_hi & (1 << bit)) != 0) as u8) << 1;
 
             if (hi | lo) == 0 {
                 continue; // color is transparent
             }
 
-            if !bg_priority || bg_color == 0 {
-                result = Some(if self.fetched_entries[i].f_pn() {
-                    self.lcd.sp2_colors[(hi | lo) as usize]
-                } else {
-                    self.lcd.sp1_colors[(hi | lo) as usize]
-                });
-
-                if (hi | lo) != 0 {
-                    break;
-                }
+            // the highest priority opaque sprite pixel wins, even if it is hidden by BG
+            if sprite.oam.f_bgp() && bg_color != 0 {
+                return None;
             }
+
+            return Some(FifoPixel {
+                color: hi | lo,
+                source: if sprite.oam.f_pn() {
+                    PixelSource::Sprite1
+                } else {
+                    PixelSource::Sprite0
+                },
+            });
         }
 
-        result
+        None
     }
 
-    fn pixel_fifo_push(&mut self, value: Color) {
+    fn pixel_fifo_push(&mut self, value: FifoPixel) {
         self.pfc.fifo.push_back(value);
     }
 
-    fn pixel_fifo_pop(&mut self) -> Color {
+    fn pixel_fifo_pop(&mut self) -> FifoPixel {
         self.pfc.fifo.pop_front().expect("PIXEL FIFO IS EMPTY!")
     }
 
     fn mode_oam(&mut self) {
-        if self.line_ticks >= 80 {
+        if self.line_ticks >= OAM_TICKS {
             self.lcd.set_lcds_mode(LcdMode::Xfer);
 
             self.pfc.current_fetch_state = FetchState::Tile;
-            self.pfc.line_x = 0;
             self.pfc.fetch_x = 0;
             self.pfc.pushed_x = 0;
-            self.pfc.fifo_x = 0;
-        }
+            self.pfc.discard = self.lcd.scroll_x % 8;
+            self.pfc.window_active = false;
+            self.pfc.window_started = false;
 
-        if self.line_ticks == 1 {
-            self.line_sprites.clear();
+            if self.window_wx166_glitch {
+                self.window_wx166_glitch = false;
+                self.pipeline_window_start();
+            }
 
             self.load_line_sprites();
         }
     }
 
-    fn mode_xfer(&mut self, bus: &mut Bus) {
-        self.pipeline_process(bus);
+    fn mode_xfer(&mut self) {
+        match self.renderer {
+            Renderer::Fifo => self.pipeline_process(),
+            Renderer::Scanline => {
+                if self.line_ticks >= OAM_TICKS + SCANLINE_XFER_TICKS {
+                    self.render_scanline();
+                }
+            }
+        }
+
         if self.pfc.pushed_x >= X_RES as u8 {
             self.pipeline_fifo_reset();
 
-            self.lcd.set_lcds_mode(LcdMode::HBlank);
+            if self.pfc.window_started {
+                self.window_line = self.window_line.wrapping_add(1);
+            }
 
-            if self.lcd.get_stat_interrupt(StatInterruptSource::HBlank) != 0 {
-                bus.interrupts.enable_flag(Interrupt::LcdStat);
+            if self.window_y_triggered && self.lcd.is_window_visible() && self.lcd.win_x == 166 {
+                self.window_wx166_glitch = true;
             }
+
+            self.lcd.set_lcds_mode(LcdMode::HBlank);
         }
     }
 
-    fn mode_vblank(&mut self, bus: &mut Bus) {
+    fn mode_vblank(&mut self) {
         if self.line_ticks >= TICKS_PER_LINE {
-            if let Some(interrupt) = self.increment_ly() {
-                bus.interrupts.enable_flag(interrupt);
-            }
+            self.increment_ly();
 
             if self.lcd.ly >= (LINES_PER_FRAME as u8) {
-                self.lcd.set_lcds_mode(LcdMode::Oam);
                 self.lcd.ly = 0;
                 self.window_line = 0;
+                self.window_y_triggered = false;
+                self.window_wx166_glitch = false;
+                self.enter_oam_mode();
             }
 
             self.line_ticks = 0;
@@ -318,24 +548,22 @@ impl Ppu {
 
     fn mode_hblank(&mut self, bus: &mut Bus) {
         if self.line_ticks >= TICKS_PER_LINE {
-            if let Some(interrupt) = self.increment_ly() {
-                bus.interrupts.enable_flag(interrupt);
-            }
+            self.increment_ly();
 
             if self.lcd.ly >= Y_RES as u8 {
                 self.lcd.set_lcds_mode(LcdMode::VBlank);
 
                 bus.interrupts.enable_flag(Interrupt::VBlank);
 
-                if self.lcd.get_stat_interrupt(StatInterruptSource::VBlank) != 0 {
-                    bus.interrupts.enable_flag(Interrupt::LcdStat);
-                }
-
                 self.current_frame += 1;
 
-                self.draw_frame(bus);
+                if self.skip_frame {
+                    self.skip_frame = false;
+                } else {
+                    self.draw_frame(bus);
+                }
             } else {
-                self.lcd.set_lcds_mode(LcdMode::Oam);
+                self.enter_oam_mode();
             }
 
             self.line_ticks = 0;
@@ -343,80 +571,164 @@ impl Ppu {
     }
 
     fn pipeline_push_pixel(&mut self) {
+        self.pipeline_window_check();
+
         if self.pfc.fifo.len() > 8 {
             let pixel_data = self.pixel_fifo_pop();
 
-            if self.pfc.line_x >= self.lcd.scroll_x % 8 {
+            if self.pfc.discard > 0 {
+                self.pfc.discard -= 1;
+            } else {
+                let pixel = self.pipeline_mix_pixel(pixel_data);
                 let index = self.pfc.pushed_x as usize + self.lcd.ly as usize * X_RES as usize;
-                self.video_buffer[index] = pixel_data;
+                self.video_buffer[index] = self.pixel_color(pixel);
 
                 self.pfc.pushed_x += 1;
             }
-
-            self.pfc.line_x += 1;
         }
     }
 
-    fn pipeline_load_sprite_tile(&mut self) {
-        for elem in self.line_sprites.iter() {
-            let sprite_x = (elem.x - 8) + (self.lcd.scroll_x % 8);
+    // LCDC and palettes are sampled here, so mid-line writes affect only the following pixels
+    fn pipeline_mix_pixel(&self, bg: FifoPixel) -> FifoPixel {
+        self.mix_pixel(self.pfc.pushed_x as i32, bg)
+    }
 
-            if (sprite_x >= self.pfc.fetch_x) && (sprite_x < (self.pfc.fetch_x + 8))
-                || ((sprite_x + 8) >= self.pfc.fetch_x) && ((sprite_x + 8) < (self.pfc.fetch_x + 8))
-            {
-                let index = self.fetched_entry_count as usize;
-                self.fetched_entry_count += 1;
-                self.fetched_entries[index] = *elem;
-            }
+    fn mix_pixel(&self, x: i32, mut bg: FifoPixel) -> FifoPixel {
+        let bg_hidden = match bg.source {
+            PixelSource::Window => !self.layers.window,
+            _ => !self.layers.background,
+        };
 
-            if self.fetched_entry_count >= 3 {
-                break;
+        if self.lcd.is_bgw_enabled() == 0 || bg_hidden {
+            bg.color = 0;
+        }
+
+        if self.lcd.is_obj_enabled() != 0 && self.layers.sprites {
+            if let Some(sprite) = self.fetch_sprite_pixels(x, bg.color) {
+                return sprite;
             }
         }
+
+        bg
     }
 
-    fn pipeline_load_sprite_data(&mut self, bus: &mut Bus, offset: u8) {
-        let current_y = self.lcd.ly;
-        let sprite_heigth = self.lcd.obj_height();
+    /// Color index of the BG/window map pixel at (`map_x`, `map_y`)
+    fn map_pixel(&self, map_area: u16, map_x: u8, map_y: u8) -> u8 {
+        let mut tile = self.vram_read(map_area + (map_x / 8) as u16 + (map_y / 8) as u16 * 32);
 
-        for i in 0..self.fetched_entry_count as usize {
-            let mut tile_y = ((current_y + 16) - self.fetched_entries[i].y) * 2;
+        if self.lcd.bgw_data_area() == 0x8800 {
+            tile = tile.wrapping_add(128);
+        }
 
-            if self.fetched_entries[i].f_y_flip() {
-                // flipped Y
-                tile_y = (sprite_heigth * 2) - 2 - tile_y;
-            }
+        let address = self.lcd.bgw_data_area() + tile as u16 * 16 + (map_y % 8) as u16 * 2;
+        let bit = 7 - (map_x % 8);
 
-            let mut tile_index = self.fetched_entries[i].tile;
+        let lo = ((self.vram_read(address) & (1 << bit)) != 0) as u8;
+        let hi = (((self.vram_read(address + 1) & (1 << bit)) != 0) as u8) << 1;
 
-            if sprite_heigth == 16 {
-                tile_index &= !1;
-            }
-            let address = 0x8000 + (tile_index as u16 * 16) + tile_y as u16 + offset as u16;
-            self.pfc.fetch_entry_data[(i * 2) + offset as usize] = bus.read(address);
+        hi | lo
+    }
+
+    /// Scanline renderer: draws the whole line at once with the registers as they are now
+    fn render_scanline(&mut self) {
+        let ly = self.lcd.ly;
+
+        // window_started is already set on a WX = 166 glitch line
+        let window_x = if self.pfc.window_started {
+            Some(0)
+        } else if self.window_y_triggered && self.lcd.is_window_visible() && self.lcd.win_x != 166 {
+            Some(self.lcd.win_x as i32 - 7)
+        } else {
+            None
+        };
+
+        for x in 0..X_RES {
+            let bg = match window_x {
+                Some(window_x) if x >= window_x => {
+                    self.pfc.window_started = true;
+                    FifoPixel {
+                        color: self.map_pixel(
+                            self.lcd.win_map_area(),
+                            (x - window_x) as u8,
+                            self.window_line,
+                        ),
+                        source: PixelSource::Window,
+                    }
+                }
+                _ => FifoPixel {
+                    color: self.map_pixel(
+                        self.lcd.bg_map_area(),
+                        (x as u8).wrapping_add(self.lcd.scroll_x),
+                        ly.wrapping_add(self.lcd.scroll_y),
+                    ),
+                    source: PixelSource::Background,
+                },
+            };
+
+            let pixel = self.mix_pixel(x, bg);
+            self.video_buffer[x as usize + ly as usize * X_RES as usize] = self.pixel_color(pixel);
+        }
+
+        self.pfc.pushed_x = X_RES as u8;
+    }
+
+    fn pixel_color(&self, pixel: FifoPixel) -> Color {
+        if self.layers.overlay {
+            return OVERLAY_COLORS[pixel.source as usize][pixel.color as usize];
+        }
+
+        let palette = match pixel.source {
+            PixelSource::Background | PixelSource::Window => &self.lcd.bg_colors,
+            PixelSource::Sprite0 => &self.lcd.sp1_colors,
+            PixelSource::Sprite1 => &self.lcd.sp2_colors,
+        };
+
+        palette[pixel.color as usize]
+    }
+
+    fn pipeline_window_check(&mut self) {
+        if self.pfc.window_started
+            || !self.window_y_triggered
+            || !self.lcd.is_window_visible()
+            || self.lcd.win_x == 166
+        {
+            return;
+        }
+
+        // window starts at WX - 7, for WX < 7 the leading pixels are clipped instead
+        let start_x = (self.lcd.win_x as i32 - 7).max(0);
+
+        if self.pfc.pushed_x as i32 == start_x {
+            self.pipeline_window_start();
         }
     }
 
-    fn pipeline_fetch(&mut self, bus: &mut Bus) {
+    fn pipeline_window_start(&mut self) {
+        self.pfc.window_active = true;
+        self.pfc.window_started = true;
+
+        self.pipeline_fifo_reset();
+        self.pfc.current_fetch_state = FetchState::Tile;
+        self.pfc.fetch_x = 0;
+        self.pfc.discard = 7u8.saturating_sub(self.lcd.win_x);
+    }
+
+    fn pipeline_fetch(&mut self) {
         match self.pfc.current_fetch_state {
             FetchState::Tile => {
-                self.fetched_entry_count = 0;
-
                 if self.lcd.is_bgw_enabled() != 0 {
-                    let address = self.lcd.bg_map_area()
-                        + (self.pfc.map_x / 8) as u16
-                        + ((self.pfc.map_y / 8) as u16 * 32);
-                    self.pfc.bgw_fetch_data[0] = bus.read(address);
+                    let map_area = if self.pfc.window_active {
+                        self.lcd.win_map_area()
+                    } else {
+                        self.lcd.bg_map_area()
+                    };
+                    let address =
+                        map_area + (self.pfc.map_x / 8) as u16 + ((self.pfc.map_y / 8) as u16 * 32);
+                    self.pfc.bgw_fetch_data[0] = self.vram_read(address);
 
                     if self.lcd.bgw_data_area() == 0x8800 {
                         self.pfc.bgw_fetch_data[0] = self.pfc.bgw_fetch_data[0].wrapping_add(128);
                     }
-
-                    self.pipeline_load_window_tile(bus);
-                }
-
-                if self.lcd.is_obj_enabled() != 0 && !self.line_sprites.is_empty() {
-                    self.pipeline_load_sprite_tile();
                 }
 
                 self.pfc.current_fetch_state = FetchState::Data0;
@@ -426,9 +738,7 @@ impl Ppu {
                 let address = self.lcd.bgw_data_area()
                     + (self.pfc.bgw_fetch_data[0] as u16 * 16)
                     + (self.pfc.tile_y) as u16;
-                self.pfc.bgw_fetch_data[1] = bus.read(address);
-
-                self.pipeline_load_sprite_data(bus, 0);
+                self.pfc.bgw_fetch_data[1] = self.vram_read(address);
 
                 self.pfc.current_fetch_state = FetchState::Data1;
             }
@@ -436,9 +746,7 @@ impl Ppu {
                 let address = self.lcd.bgw_data_area()
                     + (self.pfc.bgw_fetch_data[0] as u16 * 16)
                     + (self.pfc.tile_y + 1) as u16;
-                self.pfc.bgw_fetch_data[2] = bus.read(address);
-
-                self.pipeline_load_sprite_data(bus, 1);
+                self.pfc.bgw_fetch_data[2] = self.vram_read(address);
 
                 self.pfc.current_fetch_state = FetchState::Idle;
             }
@@ -459,42 +767,42 @@ impl Ppu {
             return false;
         }
 
-        let x = (self.pfc.fetch_x.wrapping_sub(8 - (self.lcd.scroll_x % 8))) as i32;
+        let source = if self.pfc.window_active {
+            PixelSource::Window
+        } else {
+            PixelSource::Background
+        };
 
         for bit in (0u8..8).rev() {
             let lo: u8 = ((self.pfc.bgw_fetch_data[1] & (1 << bit)) != 0) as u8;
             let hi: u8 = (((self.pfc.bgw_fetch_data[2] & (1 << bit)) != 0) as u8) << 1;
 
-            let mut color: Color = self.lcd.bg_colors[(hi | lo) as usize];
-
-            if self.lcd.is_bgw_enabled() == 0 {
-                color = self.lcd.bg_colors[0];
-            }
-
-            if self.lcd.is_obj_enabled() != 0 {
-                color = if let Some(new_color) = self.fetch_sprite_pixels(hi | lo) {
-                    new_color
-                } else {
-                    color
-                }
-            }
-
-            if x >= 0 {
-                self.pixel_fifo_push(color);
-                self.pfc.fifo_x += 1;
-            }
+            self.pixel_fifo_push(FifoPixel {
+                color: hi | lo,
+                source,
+            });
         }
 
         true
     }
 
-    fn pipeline_process(&mut self, bus: &mut Bus) {
-        self.pfc.map_y = self.lcd.ly.wrapping_add(self.lcd.scroll_y);
-        self.pfc.map_x = self.pfc.fetch_x.wrapping_add(self.lcd.scroll_x);
-        self.pfc.tile_y = ((self.lcd.ly.wrapping_add(self.lcd.scroll_y)) % 8) * 2;
+    fn pipeline_process(&mut self) {
+        // disabling the window mid-line switches the fetcher back to the background
+        if self.pfc.window_active && !self.lcd.is_window_visible() {
+            self.pfc.window_active = false;
+        }
+
+        if self.pfc.window_active {
+            self.pfc.map_y = self.window_line;
+            self.pfc.map_x = self.pfc.fetch_x;
+        } else {
+            self.pfc.map_y = self.lcd.ly.wrapping_add(self.lcd.scroll_y);
+            self.pfc.map_x = self.pfc.fetch_x.wrapping_add(self.lcd.scroll_x);
+        }
+        self.pfc.tile_y = (self.pfc.map_y % 8) * 2;
 
         if self.line_ticks & 1 == 0 {
-            self.pipeline_fetch(bus);
+            self.pipeline_fetch();
         }
 
         self.pipeline_push_pixel();
@@ -503,37 +811,27 @@ impl Ppu {
     fn pipeline_fifo_reset(&mut self) {
         self.pfc.fifo.clear();
     }
+}
 
-    fn pipeline_load_window_tile(&mut self, bus: &mut Bus) {
-        if !self.lcd.is_window_visible() {
-            return;
-        }
-
-        let window_y = self.lcd.win_y;
-        let window_x = self.lcd.win_x;
-        let comp = self.pfc.fetch_x + 7;
-
-        if comp >= window_x
-            && (comp as i32) < (window_x as i32 + Y_RES + 14)
-            && self.lcd.ly >= window_x
-            && (self.lcd.ly as i32) < window_y as i32 + X_RES
-        {
-            let w_tile_y = self.window_line / 8;
-
-            self.pfc.bgw_fetch_data[0] = bus.read(
-                ((self.lcd.win_map_area() as i32
-                    + (self.pfc.fetch_x as i32 + 7 - window_x as i32) / 8)
-                    + (w_tile_y as i32 * 32)) as u16,
-            );
+impl Default for Ppu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-            if self.lcd.bgw_data_area() == 0x8800 {
-                self.pfc.bgw_fetch_data[0] += 128;
-            }
+impl LayerOptions {
+    pub const fn new() -> Self {
+        Self {
+            background: true,
+            window: true,
+            sprites: true,
+            sprite_limit: true,
+            overlay: false,
         }
     }
 }
 
-impl Default for Ppu {
+impl Default for LayerOptions {
     fn default() -> Self {
         Self::new()
     }
@@ -544,15 +842,15 @@ impl PixelFiFo {
         Self {
             current_fetch_state: FetchState::Tile,
             fifo: VecDeque::new(),
-            line_x: 0,
+            discard: 0,
             pushed_x: 0,
             fetch_x: 0,
             bgw_fetch_data: [0; 3],
-            fetch_entry_data: [0; 6],
             map_y: 0,
             map_x: 0,
             tile_y: 0,
-            fifo_x: 0,
+            window_active: false,
+            window_started: false,
         }
     }
 }
@@ -573,6 +871,22 @@ impl Oam {
         }
     }
 
+    pub fn y(&self) -> u8 {
+        self.y
+    }
+
+    pub fn x(&self) -> u8 {
+        self.x
+    }
+
+    pub fn tile(&self) -> u8 {
+        self.tile
+    }
+
+    pub fn flags(&self) -> u8 {
+        self.flags
+    }
+
     const F_CGB_PN_MASK: u8 = 0b00000111; // Bits 0-2
     const F_CGB_VRAM_BANK_MASK: u8 = 0b00001000; // Bit 3
     const F_PN_MASK: u8 = 0b00010000; // Bit 4
@@ -0,0 +1,553 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// CPU pixel-art upscalers. Each one enlarges a frame by a fixed integer factor,
+// the frontend picks a window scale that is a multiple of it.
+//
+
+use super::Color;
+
+use self::{Blend::*, CornerRule::*};
+
+// HQx similarity thresholds in YUV
+const HQ_Y_THRESHOLD: i32 = 48;
+const HQ_U_THRESHOLD: i32 = 7;
+const HQ_V_THRESHOLD: i32 = 6;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Scaler {
+    #[default]
+    Nearest,
+    Scale2x,
+    Scale3x,
+    Hq2x,
+    Xbr2x,
+}
+
+impl Scaler {
+    pub const ALL: [Scaler; 5] = [
+        Scaler::Nearest,
+        Scaler::Scale2x,
+        Scaler::Scale3x,
+        Scaler::Hq2x,
+        Scaler::Xbr2x,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Scaler::Nearest => "nearest",
+            Scaler::Scale2x => "scale2x",
+            Scaler::Scale3x => "scale3x",
+            Scaler::Hq2x => "hq2x",
+            Scaler::Xbr2x => "xbr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|scaler| scaler.name().eq_ignore_ascii_case(name))
+    }
+
+    pub const fn factor(self) -> usize {
+        match self {
+            Scaler::Nearest => 1,
+            Scaler::Scale2x | Scaler::Hq2x | Scaler::Xbr2x => 2,
+            Scaler::Scale3x => 3,
+        }
+    }
+
+    /// Scales `frame` into `out`, returns the new width and height
+    pub fn apply(
+        self,
+        frame: &[Color],
+        width: usize,
+        height: usize,
+        out: &mut Vec<Color>,
+    ) -> (usize, usize) {
+        let factor = self.factor();
+        let source = Source {
+            frame,
+            width,
+            height,
+        };
+
+        out.clear();
+        out.resize(width * factor * height * factor, 0);
+
+        match self {
+            Scaler::Nearest => out.copy_from_slice(frame),
+            Scaler::Scale2x => scale2x(&source, out),
+            Scaler::Scale3x => scale3x(&source, out),
+            Scaler::Hq2x => hq2x(&source, out),
+            Scaler::Xbr2x => xbr2x(&source, out),
+        }
+
+        (width * factor, height * factor)
+    }
+}
+
+struct Source<'a> {
+    frame: &'a [Color],
+    width: usize,
+    height: usize,
+}
+
+impl Source<'_> {
+    /// Pixel at an offset from (x, y), edges are clamped
+    #[inline(always)]
+    fn at(&self, x: usize, y: usize, dx: i32, dy: i32) -> Color {
+        let x = (x as i32 + dx).clamp(0, self.width as i32 - 1) as usize;
+        let y = (y as i32 + dy).clamp(0, self.height as i32 - 1) as usize;
+
+        self.frame[x + y * self.width]
+    }
+}
+
+#[inline(always)]
+fn unpack(color: Color) -> (i32, i32, i32) {
+    (
+        ((color >> 16) & 0xFF) as i32,
+        ((color >> 8) & 0xFF) as i32,
+        (color & 0xFF) as i32,
+    )
+}
+
+/// Weighted average of up to three colors, weights sum to `total`
+#[inline(always)]
+fn interpolate(colors: [(Color, u32); 3], total: u32) -> Color {
+    let (mut r, mut g, mut b) = (0, 0, 0);
+    for &(color, weight) in colors.iter() {
+        r += ((color >> 16) & 0xFF) * weight;
+        g += ((color >> 8) & 0xFF) * weight;
+        b += (color & 0xFF) * weight;
+    }
+
+    0xFF000000 | ((r / total) << 16) | ((g / total) << 8) | (b / total)
+}
+
+#[inline(always)]
+fn yuv(color: Color) -> (i32, i32, i32) {
+    let (r, g, b) = unpack(color);
+
+    (
+        (r * 299 + g * 587 + b * 114) / 1000,
+        ((b - r) * 500) / 1000 + 128,
+        ((r - g) * 500) / 1000 + 128,
+    )
+}
+
+/// YUV approximation of the original HQx code
+#[inline(always)]
+fn hq_yuv(color: Color) -> (i32, i32, i32) {
+    let (r, g, b) = unpack(color);
+
+    (
+        (r + g + b) >> 2,
+        128 + ((r - b) >> 2),
+        128 + ((2 * g - r - b) >> 3),
+    )
+}
+
+/// HQx color difference: true when the colors count as different
+fn hq_differ(a: Color, b: Color) -> bool {
+    if a == b {
+        return false;
+    }
+
+    let (ay, au, av) = hq_yuv(a);
+    let (by, bu, bv) = hq_yuv(b);
+
+    (ay - by).abs() > HQ_Y_THRESHOLD
+        || (au - bu).abs() > HQ_U_THRESHOLD
+        || (av - bv).abs() > HQ_V_THRESHOLD
+}
+
+/// xBR color distance
+fn xbr_distance(a: Color, b: Color) -> u32 {
+    let (ay, au, av) = yuv(a);
+    let (by, bu, bv) = yuv(b);
+
+    ((ay - by).abs() * 48 + (au - bu).abs() * 7 + (av - bv).abs() * 6) as u32
+}
+
+//   B
+// D E F
+//   H
+fn scale2x(src: &Source, out: &mut [Color]) {
+    let out_width = src.width * 2;
+
+    for y in 0..src.height {
+        for x in 0..src.width {
+            let e = src.at(x, y, 0, 0);
+            let b = src.at(x, y, 0, -1);
+            let d = src.at(x, y, -1, 0);
+            let f = src.at(x, y, 1, 0);
+            let h = src.at(x, y, 0, 1);
+
+            let mut block = [e; 4];
+            if b != h && d != f {
+                if d == b {
+                    block[0] = d;
+                }
+                if b == f {
+                    block[1] = f;
+                }
+                if d == h {
+                    block[2] = d;
+                }
+                if h == f {
+                    block[3] = f;
+                }
+            }
+
+            let index = x * 2 + y * 2 * out_width;
+            out[index] = block[0];
+            out[index + 1] = block[1];
+            out[index + out_width] = block[2];
+            out[index + out_width + 1] = block[3];
+        }
+    }
+}
+
+// A B C
+// D E F
+// G H I
+fn scale3x(src: &Source, out: &mut [Color]) {
+    let out_width = src.width * 3;
+
+    for y in 0..src.height {
+        for x in 0..src.width {
+            let a = src.at(x, y, -1, -1);
+            let b = src.at(x, y, 0, -1);
+            let c = src.at(x, y, 1, -1);
+            let d = src.at(x, y, -1, 0);
+            let e = src.at(x, y, 0, 0);
+            let f = src.at(x, y, 1, 0);
+            let g = src.at(x, y, -1, 1);
+            let h = src.at(x, y, 0, 1);
+            let i = src.at(x, y, 1, 1);
+
+            let mut block = [e; 9];
+            if b != h && d != f {
+                if d == b {
+                    block[0] = d;
+                }
+                if (d == b && e != c) || (b == f && e != a) {
+                    block[1] = b;
+                }
+                if b == f {
+                    block[2] = f;
+                }
+                if (d == b && e != g) || (d == h && e != a) {
+                    block[3] = d;
+                }
+                if (b == f && e != i) || (h == f && e != c) {
+                    block[5] = f;
+                }
+                if d == h {
+                    block[6] = d;
+                }
+                if (d == h && e != i) || (h == f && e != g) {
+                    block[7] = h;
+                }
+                if h == f {
+                    block[8] = f;
+                }
+            }
+
+            for (n, color) in block.iter().enumerate() {
+                out[x * 3 + n % 3 + (y * 3 + n / 3) * out_width] = *color;
+            }
+        }
+    }
+}
+
+// HQ2x interpolations of the top-left output pixel, named after the PIXEL00_*
+// macros of the original code. w5 is the source pixel, w1 w2 w4 its top-left,
+// top and left neighbours
+#[derive(Debug, Clone, Copy)]
+enum Blend {
+    /// w5
+    B0,
+    /// (3 w5 + w1) / 4
+    B10,
+    /// (3 w5 + w4) / 4
+    B11,
+    /// (3 w5 + w2) / 4
+    B12,
+    /// (2 w5 + w4 + w2) / 4
+    B20,
+    /// (2 w5 + w1 + w2) / 4
+    B21,
+    /// (2 w5 + w1 + w4) / 4
+    B22,
+    /// (5 w5 + 2 w2 + w4) / 8
+    B60,
+    /// (5 w5 + 2 w4 + w2) / 8
+    B61,
+    /// (6 w5 + w4 + w2) / 8
+    B70,
+    /// (2 w5 + 3 w4 + 3 w2) / 8
+    B90,
+    /// (14 w5 + w4 + w2) / 16
+    B100,
+}
+
+// Some patterns leave the choice to a second similarity test between two of the
+// neighbours: the first blend is used when they differ
+#[derive(Debug, Clone, Copy)]
+enum CornerRule {
+    Fixed(Blend),
+    /// w4 against w2
+    Own(Blend, Blend),
+    /// w2 against w6
+    Clockwise(Blend, Blend),
+    /// w8 against w4
+    CounterClockwise(Blend, Blend),
+}
+
+// Top-left rule for every pattern of neighbours that differ from w5, bit 0 is
+// w1, then w2 w3 w4 w6 w7 w8 w9. The other three output pixels use the same
+// table on the neighbourhood rotated by 90, 180 and 270 degrees
+#[rustfmt::skip]
+static HQ2X_TABLE: [CornerRule; 256] = [
+    /*   0 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*   4 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*   8 */ Fixed(B21), Fixed(B12), Own(B10, B20), Own(B0, B20),
+    /*  12 */ Fixed(B21), Fixed(B12), Own(B10, B60), Own(B0, B20),
+    /*  16 */ Fixed(B20), Fixed(B20), Fixed(B22), Clockwise(B11, B90),
+    /*  20 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  24 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /*  28 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /*  32 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  36 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  40 */ Fixed(B21), Fixed(B12), Own(B10, B61), Own(B0, B20),
+    /*  44 */ Fixed(B21), Fixed(B12), Own(B10, B20), Own(B0, B20),
+    /*  48 */ Fixed(B20), Fixed(B20), Fixed(B22), Clockwise(B11, B90),
+    /*  52 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  56 */ Fixed(B21), Fixed(B12), Own(B10, B61), Own(B0, B20),
+    /*  60 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /*  64 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  68 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  72 */ Fixed(B21), CounterClockwise(B12, B90), Own(B10, B70), Own(B0, B20),
+    /*  76 */ Fixed(B21), CounterClockwise(B12, B90), Own(B10, B60), Own(B0, B20),
+    /*  80 */ Fixed(B20), Fixed(B20), Fixed(B22), Clockwise(B11, B90),
+    /*  84 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /*  88 */ Fixed(B21), CounterClockwise(B12, B90), Own(B10, B70), Own(B0, B20),
+    /*  92 */ Fixed(B21), CounterClockwise(B12, B90), Own(B10, B70), Own(B0, B20),
+    /*  96 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 100 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 104 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 108 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 112 */ Fixed(B20), Fixed(B20), Fixed(B22), Clockwise(B11, B90),
+    /* 116 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 120 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 124 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B100),
+    /* 128 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 132 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 136 */ Fixed(B21), Fixed(B12), Own(B10, B20), Own(B0, B20),
+    /* 140 */ Fixed(B21), Fixed(B12), Own(B10, B60), Own(B0, B20),
+    /* 144 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 148 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 152 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 156 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 160 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 164 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 168 */ Fixed(B21), Fixed(B12), Own(B10, B61), Own(B0, B20),
+    /* 172 */ Fixed(B21), Fixed(B12), Own(B10, B20), Own(B0, B20),
+    /* 176 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 180 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 184 */ Fixed(B21), Fixed(B12), Own(B10, B61), Own(B0, B20),
+    /* 188 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 192 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 196 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 200 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 204 */ Fixed(B21), Fixed(B12), Own(B10, B60), Own(B0, B20),
+    /* 208 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 212 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 216 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 220 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 224 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 228 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 232 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 236 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 240 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 244 */ Fixed(B20), Fixed(B20), Fixed(B22), Fixed(B11),
+    /* 248 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B20),
+    /* 252 */ Fixed(B21), Fixed(B12), Own(B10, B70), Own(B0, B100),
+];
+
+//   w1 w2 w3
+//   w4 w5 w6
+//   w7 w8 w9
+fn hq2x(src: &Source, out: &mut [Color]) {
+    let out_width = src.width * 2;
+
+    for y in 0..src.height {
+        for x in 0..src.width {
+            let w5 = src.at(x, y, 0, 0);
+            let index = x * 2 + y * 2 * out_width;
+
+            // top-left, top-right, bottom-right, bottom-left
+            for rotation in 0..4 {
+                // (dx, dy) rotated by 90 degrees `rotation` times
+                let rotate =
+                    |dx: i32, dy: i32| (0..rotation).fold((dx, dy), |(dx, dy), _| (-dy, dx));
+                let px = |dx: i32, dy: i32| {
+                    let (dx, dy) = rotate(dx, dy);
+                    src.at(x, y, dx, dy)
+                };
+
+                let w = [
+                    px(-1, -1),
+                    px(0, -1),
+                    px(1, -1),
+                    px(-1, 0),
+                    w5,
+                    px(1, 0),
+                    px(-1, 1),
+                    px(0, 1),
+                    px(1, 1),
+                ];
+                let (w1, w2, w4, w6, w8) = (w[0], w[1], w[3], w[5], w[7]);
+
+                let mut pattern = 0;
+                for (bit, neighbour) in [0, 1, 2, 3, 5, 6, 7, 8].iter().enumerate() {
+                    if hq_differ(w5, w[*neighbour]) {
+                        pattern |= 1 << bit;
+                    }
+                }
+
+                let blend = match HQ2X_TABLE[pattern] {
+                    Fixed(blend) => blend,
+                    Own(differ, same) => pick(hq_differ(w4, w2), differ, same),
+                    Clockwise(differ, same) => pick(hq_differ(w2, w6), differ, same),
+                    CounterClockwise(differ, same) => pick(hq_differ(w8, w4), differ, same),
+                };
+
+                let color = match blend {
+                    B0 => w5,
+                    B10 => interpolate([(w5, 3), (w1, 1), (w5, 0)], 4),
+                    B11 => interpolate([(w5, 3), (w4, 1), (w5, 0)], 4),
+                    B12 => interpolate([(w5, 3), (w2, 1), (w5, 0)], 4),
+                    B20 => interpolate([(w5, 2), (w4, 1), (w2, 1)], 4),
+                    B21 => interpolate([(w5, 2), (w1, 1), (w2, 1)], 4),
+                    B22 => interpolate([(w5, 2), (w1, 1), (w4, 1)], 4),
+                    B60 => interpolate([(w5, 5), (w2, 2), (w4, 1)], 8),
+                    B61 => interpolate([(w5, 5), (w4, 2), (w2, 1)], 8),
+                    B70 => interpolate([(w5, 6), (w4, 1), (w2, 1)], 8),
+                    B90 => interpolate([(w5, 2), (w4, 3), (w2, 3)], 8),
+                    B100 => interpolate([(w5, 14), (w4, 1), (w2, 1)], 16),
+                };
+
+                let (dx, dy) = rotate(-1, -1);
+                out[index + ((dx + 1) / 2) as usize + ((dy + 1) / 2) as usize * out_width] = color;
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn pick(differ: bool, if_differ: Blend, otherwise: Blend) -> Blend {
+    if differ {
+        if_differ
+    } else {
+        otherwise
+    }
+}
+
+// 2xBR level 1. The bottom-right corner rule is run for all four corners by
+// rotating the neighbourhood; offsets below are written for the bottom-right case:
+//
+//       A1 B1 C1
+//    A0 A  B  C  C4
+//    D0 D  E  F  F4
+//    G0 G  H  I  I4
+//       G5 H5 I5
+fn xbr2x(src: &Source, out: &mut [Color]) {
+    let out_width = src.width * 2;
+
+    for y in 0..src.height {
+        for x in 0..src.width {
+            let e = src.at(x, y, 0, 0);
+            let mut block = [e; 4];
+
+            for rotation in 0..4 {
+                // (dx, dy) rotated by 90 degrees `rotation` times
+                let rotate =
+                    |dx: i32, dy: i32| (0..rotation).fold((dx, dy), |(dx, dy), _| (-dy, dx));
+                let px = |dx: i32, dy: i32| {
+                    let (dx, dy) = rotate(dx, dy);
+                    src.at(x, y, dx, dy)
+                };
+                let sub = |dx: i32, dy: i32| {
+                    let (dx, dy) = rotate(dx, dy);
+                    ((dx + 1) / 2 + (dy + 1)) as usize
+                };
+
+                let (b, c, d, f, g, h, i) = (
+                    px(0, -1),
+                    px(1, -1),
+                    px(-1, 0),
+                    px(1, 0),
+                    px(-1, 1),
+                    px(0, 1),
+                    px(1, 1),
+                );
+                let (f4, h5, i4, i5) = (px(2, 0), px(0, 2), px(2, 1), px(1, 2));
+
+                let edge = xbr_distance(e, c)
+                    + xbr_distance(e, g)
+                    + xbr_distance(i, f4)
+                    + xbr_distance(i, h5)
+                    + 4 * xbr_distance(h, f);
+                let interior = xbr_distance(h, d)
+                    + xbr_distance(h, i5)
+                    + xbr_distance(f, i4)
+                    + xbr_distance(f, b)
+                    + 4 * xbr_distance(e, i);
+
+                if edge >= interior {
+                    continue;
+                }
+
+                let new = if xbr_distance(e, f) <= xbr_distance(e, h) {
+                    f
+                } else {
+                    h
+                };
+
+                let ke = xbr_distance(f, g);
+                let ki = xbr_distance(h, c);
+                let shallow = ke * 2 <= ki && e != g && d != g;
+                let steep = ki * 2 <= ke && e != c && b != c;
+
+                let corner = sub(1, 1);
+                let blend = |block: &mut [Color; 4], n: usize, weight: u32| {
+                    block[n] = interpolate([(block[n], 4 - weight), (new, weight), (e, 0)], 4);
+                };
+
+                if shallow || steep {
+                    blend(&mut block, corner, 3);
+                    if shallow {
+                        blend(&mut block, sub(-1, 1), 1);
+                    }
+                    if steep {
+                        blend(&mut block, sub(1, -1), 1);
+                    }
+                } else {
+                    blend(&mut block, corner, 2);
+                }
+            }
+
+            let index = x * 2 + y * 2 * out_width;
+            out[index] = block[0];
+            out[index + 1] = block[1];
+            out[index + out_width] = block[2];
+            out[index + out_width + 1] = block[3];
+        }
+    }
+}
@@ -13,37 +13,39 @@ pub struct GamepadState {
     pub right: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Selector {
-    Button,
-    Direction,
-}
+// P1 (FF00): bits 4 (P14, directions) and 5 (P15, buttons) select a group
+// when low, P10-P13 read the selected keys ANDed together, pressed is 0
+const SELECT_MASK: u8 = 0x30;
+const LINES_MASK: u8 = 0x0F;
 
 #[derive(Debug)]
 pub struct Gamepad {
     pub state: GamepadState,
-    selector: Selector,
+    select: u8,
+    // P10-P13 as last seen, to catch high to low transitions
+    lines: u8,
+    interrupt: bool,
 }
 
-
 impl Gamepad {
     pub fn new() -> Self {
         Self {
-            selector: Selector::Button,
+            // both groups selected after boot, P1 reads 0xCF
+            select: 0,
             state: GamepadState::default(),
+            lines: LINES_MASK,
+            interrupt: false,
         }
     }
 
     pub fn set_selector(&mut self, value: u8) {
-        self.selector = match (value & 0x20 != 0, value & 0x10 != 0) {
-            (true, _) => Selector::Button,
-            (_, true) => Selector::Direction,
-            _ => self.selector,
-        };
+        self.select = value & SELECT_MASK;
+        self.update_lines();
     }
 
     pub fn set_state(&mut self, state: GamepadState) {
         self.state = state;
+        self.update_lines();
     }
 
     pub fn get_state(&self) -> &GamepadState {
@@ -54,41 +56,43 @@ impl Gamepad {
         &mut self.state
     }
 
-    pub fn calculate_output(&self) -> u8 {
-        let mut output = 0xCF;
-
-        match self.selector {
-            Selector::Button => {
-                if self.state.start {
-                    output &= !(1 << 3);
-                }
-                if self.state.select {
-                    output &= !(1 << 2);
-                }
-                if self.state.a {
-                    output &= !(1 << 0);
-                }
-                if self.state.b {
-                    output &= !(1 << 1);
-                }
-            }
-            Selector::Direction => {
-                if self.state.left {
-                    output &= !(1 << 1);
-                }
-                if self.state.right {
-                    output &= !(1 << 0);
-                }
-                if self.state.up {
-                    output &= !(1 << 2);
-                }
-                if self.state.down {
-                    output &= !(1 << 3);
-                }
+    /// Joypad interrupt raised since the last call
+    pub fn take_interrupt(&mut self) -> bool {
+        std::mem::take(&mut self.interrupt)
+    }
+
+    fn update_lines(&mut self) {
+        let lines = self.calculate_output() & LINES_MASK;
+
+        if self.lines & !lines != 0 {
+            self.interrupt = true;
+        }
+        self.lines = lines;
+    }
+
+    fn pressed(keys: [bool; 4]) -> u8 {
+        let mut lines = LINES_MASK;
+        for (bit, pressed) in keys.iter().enumerate() {
+            if *pressed {
+                lines &= !(1 << bit);
             }
         }
 
-        output
+        lines
+    }
+
+    pub fn calculate_output(&self) -> u8 {
+        let state = &self.state;
+        let mut lines = LINES_MASK;
+
+        if self.select & 0x10 == 0 {
+            lines &= Self::pressed([state.right, state.left, state.up, state.down]);
+        }
+        if self.select & 0x20 == 0 {
+            lines &= Self::pressed([state.a, state.b, state.select, state.start]);
+        }
+
+        0xC0 | self.select | lines
     }
 }
 
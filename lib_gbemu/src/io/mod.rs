@@ -2,4 +2,7 @@
 // SPDX-License-Identifier: gpl-3.0-only
 
 pub mod timer;
-pub mod input;
\ No newline at end of file
+pub mod input;
+pub mod serial;
+pub mod sound;
+pub mod speed;
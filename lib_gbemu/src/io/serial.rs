@@ -0,0 +1,163 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Serial port, SB (FF01) and SC (FF02). With the internal clock a byte is
+// shifted out MSB first at 8192 Hz, one bit on every falling edge of DIV bit 8.
+// With the external clock the port waits until the peer clocks a byte in.
+//
+
+use crate::memory::interrupts::{Interrupt, InterruptState};
+
+use std::io::Write;
+
+const SC_TRANSFER: u8 = 0x80;
+const SC_INTERNAL_CLOCK: u8 = 0x01;
+// internal counter bit driving the 8192 Hz clock
+const CLOCK_BIT: u16 = 1 << 8;
+
+/// Other end of the link cable, exchanges whole bytes
+pub trait SerialLink {
+    /// This side drives the clock: `value` goes out, the peer's byte comes back
+    fn transfer(&mut self, value: u8) -> u8;
+
+    /// Peer drives the clock: returns its byte once it sent one, `value` is
+    /// what the peer receives in exchange
+    fn clock_in(&mut self, _value: u8) -> Option<u8> {
+        None
+    }
+}
+
+/// Nothing connected: the input line floats high, no external clock ever comes
+#[derive(Debug, Default)]
+pub struct NoLink;
+
+impl SerialLink for NoLink {
+    fn transfer(&mut self, _value: u8) -> u8 {
+        0xFF
+    }
+}
+
+/// Writes every line sent over the cable to `writer`, test ROMs report
+/// results this way
+#[derive(Debug)]
+pub struct LogLink<W: Write> {
+    pub output: String,
+    pub writer: W,
+}
+
+impl<W: Write> LogLink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            output: String::new(),
+            writer,
+        }
+    }
+}
+
+impl<W: Write> SerialLink for LogLink<W> {
+    fn transfer(&mut self, value: u8) -> u8 {
+        self.output.push(value as char);
+        if value == b'\n' {
+            let _ = write!(self.writer, "SERIAL: {}", self.output);
+            let _ = self.writer.flush();
+            self.output.clear();
+        }
+
+        0xFF
+    }
+}
+
+pub struct Serial {
+    pub sb: u8,
+    pub sc: u8,
+    pub link: Box<dyn SerialLink>,
+
+    // byte being sent, handed to the link once the eighth bit is out
+    outgoing: u8,
+    bits_left: u8,
+    clock: bool,
+}
+
+impl Serial {
+    pub fn new() -> Self {
+        Self {
+            sb: 0,
+            sc: 0,
+            link: Box::new(NoLink),
+            outgoing: 0xFF,
+            bits_left: 0,
+            clock: false,
+        }
+    }
+
+    pub fn is_transfering(&self) -> bool {
+        self.sc & SC_TRANSFER != 0
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        match address {
+            0xFF01 => self.sb,
+            // DMG has no clock speed bit, bits 1-6 read as 1
+            _ => self.sc | 0x7E,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            0xFF01 => self.sb = value,
+            _ => {
+                self.sc = value & (SC_TRANSFER | SC_INTERNAL_CLOCK);
+
+                if self.is_transfering() && self.sc & SC_INTERNAL_CLOCK != 0 {
+                    self.outgoing = self.sb;
+                    self.bits_left = 8;
+                }
+            }
+        }
+    }
+
+    /// Called every T-cycle with the timer's internal counter
+    pub fn tick(&mut self, div: u16, interrupts: &mut InterruptState) {
+        let clock = div & CLOCK_BIT != 0;
+        let falling_edge = self.clock && !clock;
+        self.clock = clock;
+
+        if !self.is_transfering() {
+            return;
+        }
+
+        if self.sc & SC_INTERNAL_CLOCK == 0 {
+            if let Some(value) = self.link.clock_in(self.sb) {
+                self.sb = value;
+                self.finish(interrupts);
+            }
+            return;
+        }
+
+        if !falling_edge {
+            return;
+        }
+
+        // the peer's bits are only known once the whole byte is exchanged,
+        // until then the input line reads high
+        self.bits_left -= 1;
+        self.sb = (self.sb << 1) | 1;
+
+        if self.bits_left == 0 {
+            self.sb = self.link.transfer(self.outgoing);
+            self.finish(interrupts);
+        }
+    }
+
+    fn finish(&mut self, interrupts: &mut InterruptState) {
+        self.sc &= !SC_TRANSFER;
+        interrupts.enable_flag(Interrupt::Serial);
+    }
+}
+
+impl Default for Serial {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Sound registers FF10-FF3F. No audio is produced, the registers only keep
+// what was written so reads return it with the write-only bits set.
+//
+
+const NR52_ADDRESS: u16 = 0xFF26;
+const WAVE_RAM_ADDRESS: u16 = 0xFF30;
+
+// bits that always read as 1, FF10-FF2F
+const READ_MASKS: [u8; 0x20] = [
+    0x80, 0x3F, 0x00, 0xFF, 0xBF, // NR10-NR14
+    0xFF, 0x3F, 0x00, 0xFF, 0xBF, // NR21-NR24
+    0x7F, 0xFF, 0x9F, 0xFF, 0xBF, // NR30-NR34
+    0xFF, 0xFF, 0x00, 0x00, 0xBF, // NR41-NR44
+    0x00, 0x00, 0x70, // NR50-NR52
+    0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+];
+
+#[derive(Debug)]
+pub struct SoundRegisters {
+    regs: [u8; 0x30],
+}
+
+impl SoundRegisters {
+    pub const fn new() -> Self {
+        // the boot ROM leaves the APU powered on
+        let mut regs = [0; 0x30];
+        regs[(NR52_ADDRESS - 0xFF10) as usize] = 0x80;
+
+        Self { regs }
+    }
+
+    fn is_powered(&self) -> bool {
+        self.regs[(NR52_ADDRESS - 0xFF10) as usize] & 0x80 != 0
+    }
+
+    pub fn read(&self, address: u16) -> u8 {
+        let offset = (address - 0xFF10) as usize;
+
+        match address {
+            WAVE_RAM_ADDRESS.. => self.regs[offset],
+            // channel status bits stay 0, no channel ever plays
+            NR52_ADDRESS => READ_MASKS[offset] | (self.regs[offset] & 0x80),
+            _ => READ_MASKS[offset] | self.regs[offset],
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        let offset = (address - 0xFF10) as usize;
+
+        match address {
+            WAVE_RAM_ADDRESS.. => self.regs[offset] = value,
+            NR52_ADDRESS => {
+                // powering off clears every register up to NR51
+                if value & 0x80 == 0 {
+                    self.regs[..offset].fill(0);
+                }
+                self.regs[offset] = value & 0x80;
+            }
+            // ignored while powered off
+            _ if !self.is_powered() => (),
+            _ => self.regs[offset] = value,
+        }
+    }
+}
+
+impl Default for SoundRegisters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// CGB speed switch, KEY1 (FF4D): bit 0 arms the switch, the next STOP performs
+// it and bit 7 reads the current speed. Only CGB-aware cartridges get KEY1.
+//
+
+pub const KEY1_ADDRESS: u16 = 0xFF4D;
+/// M-cycles the CPU is stopped while the clock switches
+pub const SWITCH_CYCLES: i32 = 2050;
+
+#[derive(Debug, Default)]
+pub struct SpeedSwitch {
+    pub double_speed: bool,
+    pub armed: bool,
+}
+
+impl SpeedSwitch {
+    pub const fn new() -> Self {
+        Self {
+            double_speed: false,
+            armed: false,
+        }
+    }
+
+    pub fn read(&self) -> u8 {
+        0x7E | ((self.double_speed as u8) << 7) | self.armed as u8
+    }
+
+    pub fn write(&mut self, value: u8) {
+        self.armed = value & 0x01 != 0;
+    }
+
+    pub fn switch(&mut self) {
+        self.double_speed = !self.double_speed;
+        self.armed = false;
+    }
+}
@@ -3,6 +3,12 @@
 
 use crate::memory::{interrupts::Interrupt, Bus};
 
+// TIMA counts falling edges of one DIV bit (picked by TAC) ANDed with the
+// enable bit, so DIV and TAC writes can produce extra increments.
+// After an overflow TIMA reads 0 for one M-cycle, then TMA is loaded and the
+// interrupt requested; writes in either window have special effects.
+const RELOAD_DELAY: u8 = 4;
+
 #[derive(Debug)]
 pub struct Timer {
     pub div: u16,
@@ -11,6 +17,11 @@ pub struct Timer {
     pub tac: u8,
 
     pub ticks: u32,
+
+    // T-cycles left until TMA is loaded after an overflow
+    reload_delay: u8,
+    // T-cycles left in the M-cycle TMA was loaded on
+    reloading: u8,
 }
 
 impl Timer {
@@ -21,43 +32,74 @@ impl Timer {
             tma: 0,
             tac: 0,
             ticks: 0,
+            reload_delay: 0,
+            reloading: 0,
+        }
+    }
+
+    // input of the falling edge detector
+    fn signal(&self) -> bool {
+        let bit = match self.tac & 0b11 {
+            0b00 => 9,
+            0b01 => 3,
+            0b10 => 5,
+            _ => 7,
+        };
+
+        self.tac & (1 << 2) != 0 && self.div & (1 << bit) != 0
+    }
+
+    fn increment_tima(&mut self) {
+        let (tima, overflow) = self.tima.overflowing_add(1);
+        self.tima = tima;
+
+        if overflow {
+            self.reload_delay = RELOAD_DELAY;
         }
     }
 
     pub fn tick(&mut self, bus: &mut Bus) {
-        let prev_divider = self.div;
-        self.div = self.div.wrapping_add(1);
+        self.reloading = self.reloading.saturating_sub(1);
 
-        let timer_update = match self.tac & 0b11 {
-            0b00 => (prev_divider & (1 << 9)) != 0 && self.div & (1 << 9) == 0,
-            0b01 => (prev_divider & (1 << 3)) != 0 && self.div & (1 << 3) == 0,
-            0b10 => (prev_divider & (1 << 5)) != 0 && self.div & (1 << 5) == 0,
-            0b11 => (prev_divider & (1 << 7)) != 0 && self.div & (1 << 7) == 0,
-            _ => false,
-        };
+        if self.reload_delay > 0 {
+            self.reload_delay -= 1;
 
-        if timer_update && (self.tac & (1 << 2)) != 0 {
-            if self.tima == 0xFF {
+            if self.reload_delay == 0 {
                 self.tima = self.tma;
+                self.reloading = RELOAD_DELAY;
                 bus.interrupts.enable_flag(Interrupt::Timer);
             }
-            self.tima += 1;
+        }
+
+        let prev_signal = self.signal();
+        self.div = self.div.wrapping_add(1);
+
+        if prev_signal && !self.signal() {
+            self.increment_tima();
         }
     }
 
     pub fn write(&mut self, address: u16, value: u8) {
+        let prev_signal = self.signal();
+
         match address {
             0xFF04 => {
                 // DIV
                 self.div = 0;
             }
             0xFF05 => {
-                // TIMA
-                self.tima = value;
+                // TIMA: cancels a pending reload, ignored while TMA is loaded
+                if self.reloading == 0 {
+                    self.tima = value;
+                    self.reload_delay = 0;
+                }
             }
             0xFF06 => {
-                // TMA
+                // TMA: goes straight to TIMA during the reload M-cycle
                 self.tma = value;
+                if self.reloading > 0 {
+                    self.tima = value;
+                }
             }
             0xFF07 => {
                 // TAC
@@ -65,6 +107,10 @@ impl Timer {
             }
             _ => panic!("UNSUPPORTED TIMER WRITE: {:04X}, {:02X}", address, value),
         }
+
+        if prev_signal && !self.signal() {
+            self.increment_tima();
+        }
     }
 
     pub fn read(&self, address: u16) -> u8 {
@@ -10,6 +10,5 @@ pub mod cpu;
 pub mod emu;
 pub mod io;
 pub mod memory;
-pub mod debug;
 pub mod gpu;
 pub mod common;
\ No newline at end of file
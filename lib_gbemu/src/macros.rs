@@ -11,21 +11,19 @@ macro_rules! bit {
 /// Creates 2nd mutable reference from a mutable reference
 #[macro_export]
 macro_rules! make_mut_ref {
-    ($value:expr) => {
-        unsafe {
-            &mut *($value as *mut _)
-        }
-    };
+    ($value:expr) => {{
+        let ptr = $value as *mut _;
+        unsafe { &mut *ptr }
+    }};
 }
 
 /// Creates 2nd reference from ANY reference (mutable/immutable)
 #[macro_export]
 macro_rules! make_ref {
-    ($value:expr) => {
-        unsafe {
-            &*($value as *const _)
-        }
-    };
+    ($value:expr) => {{
+        let ptr = $value as *const _;
+        unsafe { &*ptr }
+    }};
 }
 
 #[macro_export]
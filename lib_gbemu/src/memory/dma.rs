@@ -1,14 +1,28 @@
 // Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
 // SPDX-License-Identifier: gpl-3.0-only
 
+//
+// OAM DMA, one byte per M-cycle. A write to FF46 is picked up at the end of its
+// M-cycle, the next M-cycle sets the transfer up and the first byte is copied on
+// the one after. While bytes are being copied the CPU only reaches FF00-FFFF,
+// everything else reads the byte on the DMA bus.
+//
+
 use super::Bus;
 
+const OAM_SIZE: u8 = 0xA0;
+
 #[derive(Debug)]
 pub struct Dma {
     is_active: bool,
     byte: u8,
     value: u8,
-    start_delay: u8,
+    // FF46 written during the current M-cycle
+    requested: Option<u8>,
+    // setup M-cycle, a running transfer keeps going until it ends
+    starting: Option<u8>,
+    // last byte put on the bus, seen by conflicting CPU reads
+    bus_value: u8,
 }
 
 impl Dma {
@@ -17,39 +31,42 @@ impl Dma {
             is_active: false,
             byte: 0,
             value: 0,
-            start_delay: 0,
+            requested: None,
+            starting: None,
+            bus_value: 0xFF,
         }
     }
 
+    /// A write while a transfer is running restarts it once the new one is set up
     pub fn start(&mut self, start: u8) {
-        self.is_active = true;
-        self.byte = 0;
-        self.value = start;
-        self.start_delay = 2;
+        self.requested = Some(start);
     }
 
     pub fn is_transfering(&self) -> bool {
         self.is_active
     }
 
+    pub fn bus_value(&self) -> u8 {
+        self.bus_value
+    }
+
     pub fn tick(&mut self, bus: &mut Bus) {
-        if !self.is_active {
-            return;
+        if let Some(start) = self.starting.take() {
+            self.is_active = true;
+            self.byte = 0;
+            self.value = start;
         }
+        self.starting = self.requested.take();
 
-        if self.start_delay > 0 {
-            self.start_delay -= 1;
+        if !self.is_active {
             return;
         }
 
-        let data = bus.read(
-            (self.value as u16)
-                .wrapping_mul(0x100)
-                .wrapping_add(self.byte as u16),
-        );
+        let data = bus.dma_read(((self.value as u16) << 8) | self.byte as u16);
         bus.ppu.oam_write(self.byte as u16, data);
+        self.bus_value = data;
 
-        self.byte = self.byte.wrapping_add(1);
-        self.is_active = self.byte < 0xA0;
+        self.byte += 1;
+        self.is_active = self.byte < OAM_SIZE;
     }
-}
\ No newline at end of file
+}
@@ -7,7 +7,7 @@ use crate::memory::Bus;
 pub const INTERRUPT_ENABLE_ADDRESS: u16 = 0xFFFF;
 pub const INTERRUPT_FLAGS_ADDRESS: u16 = 0xFF0F;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Interrupt {
     VBlank = 0x01,
@@ -18,6 +18,15 @@ pub enum Interrupt {
 }
 
 impl Interrupt {
+    /// Highest priority first
+    pub const ALL: [Interrupt; 5] = [
+        Interrupt::VBlank,
+        Interrupt::LcdStat,
+        Interrupt::Timer,
+        Interrupt::Serial,
+        Interrupt::Joypad,
+    ];
+
     const fn address(self) -> u16 {
         match self {
             Interrupt::VBlank => 0x0040,
@@ -33,6 +42,8 @@ impl Interrupt {
 pub struct InterruptState {
     pub flags: u8,
     pub enabled: u8,
+    // bits requested since the event recorder last looked
+    pub requested: u8,
 }
 
 impl InterruptState {
@@ -40,11 +51,13 @@ impl InterruptState {
         Self {
             flags: 0,
             enabled: 0,
+            requested: 0,
         }
     }
 
     pub fn enable_flag(&mut self, interrupt: Interrupt) {
         self.flags |= interrupt as u8;
+        self.requested |= interrupt as u8;
     }
 
     pub fn is_active(&self, interrupt: Interrupt) -> bool {
@@ -58,7 +71,15 @@ impl InterruptState {
     }
 
     pub fn has_any_flag(&self) -> bool {
-        (self.flags & self.enabled) != 0
+        (self.flags & self.enabled & 0x1F) != 0
+    }
+
+    /// Highest priority interrupt both requested and enabled
+    pub fn pending(&self) -> Option<Interrupt> {
+        Interrupt::ALL
+            .iter()
+            .copied()
+            .find(|interrupt| self.is_active(*interrupt))
     }
 }
 
@@ -69,31 +90,34 @@ impl Default for InterruptState {
 }
 
 impl Cpu {
+    // Dispatch takes 5 M-cycles: two internal ones, the PC high and low byte
+    // pushes and the jump. The vector is picked only after the high byte push,
+    // which can overwrite IE at 0xFFFF: with nothing left pending the CPU
+    // jumps to 0x0000 instead.
     pub fn handle_interrupts(&mut self, bus: &mut Bus) {
-        match () {
-            _ if self.process_interrupt(bus, Interrupt::VBlank) => (),
-            _ if self.process_interrupt(bus, Interrupt::LcdStat) => (),
-            _ if self.process_interrupt(bus, Interrupt::Timer) => (),
-            _ if self.process_interrupt(bus, Interrupt::Serial) => (),
-            _ if self.process_interrupt(bus, Interrupt::Joypad) => (),
-            _ => (),
+        if !bus.interrupts.has_any_flag() {
+            return;
         }
-    }
 
-    fn process_interrupt(&mut self, bus: &mut Bus, interrupt: Interrupt) -> bool {
-        let address = interrupt.address();
+        self.is_halted = false;
+        self.interrupt_master_enabled = false;
 
-        if !bus.interrupts.is_active(interrupt) {
-            return false;
+        // EI; HALT with a pending interrupt returns to the HALT itself
+        if self.halt_bug {
+            self.halt_bug = false;
+            self.regs.pc = self.regs.pc.wrapping_sub(1);
         }
 
-        self.stack_push16(self.regs.pc, bus);
-        self.regs.pc = address;
+        bus.cycle(2);
+        self.stack_push((self.regs.pc >> 8) as u8, bus);
 
-        bus.interrupts.remove_flag(interrupt);
-        self.is_halted = false;
-        self.interrupt_master_enabled = false;
+        let interrupt = bus.interrupts.pending();
+        if let Some(interrupt) = interrupt {
+            bus.interrupts.remove_flag(interrupt);
+        }
 
-        true
+        self.stack_push(self.regs.pc as u8, bus);
+        self.regs.pc = interrupt.map_or(0x0000, Interrupt::address);
+        bus.cycle(1);
     }
 }
@@ -9,7 +9,7 @@
 // 0xA000 - 0xBFFF : Cartridge RAM
 // 0xC000 - 0xCFFF : RAM Bank 0
 // 0xD000 - 0xDFFF : RAM Bank 1-7 - switchable - Color only
-// 0xE000 - 0xFDFF : Reserved - Echo RAM
+// 0xE000 - 0xFDFF : Echo RAM - mirrors 0xC000 - 0xDDFF
 // 0xFE00 - 0xFE9F : Object Attribute Memory
 // 0xFEA0 - 0xFEFF : Reserved - Unusable
 // 0xFF00 - 0xFF7F : I/O Registers
@@ -25,8 +25,18 @@ use self::{dma::Dma, interrupts::*, ram::Ram};
 use crate::{
     cartridge::rom::Rom,
     emu::Emu,
-    gpu::{ppu::Ppu, GbWindow},
-    io::{input::Gamepad, timer::Timer},
+    gpu::{
+        events::{EventKind, EventRecorder},
+        ppu::Ppu,
+        GbWindow,
+    },
+    io::{
+        input::Gamepad,
+        serial::Serial,
+        sound::SoundRegisters,
+        speed::{SpeedSwitch, KEY1_ADDRESS},
+        timer::Timer,
+    },
 };
 
 pub struct Bus<'a> {
@@ -38,15 +48,27 @@ pub struct Bus<'a> {
     dma: Dma,
     pub emu: Emu,
     pub timer: Timer,
+    pub sound: SoundRegisters,
 
     pub gamepad: Gamepad,
     pub screen: &'a mut dyn GbWindow,
+    pub events: EventRecorder,
+    /// STOP mode: timer, PPU and DMA are frozen until a joypad line goes low
+    pub stopped: bool,
+    /// KEY1, only present for CGB cartridges
+    pub speed: Option<SpeedSwitch>,
 
-    serial_data: [u8; 2],
+    pub serial: Serial,
 }
 
 impl<'a> Bus<'a> {
     pub fn new(rom: Rom, screen: &'a mut dyn GbWindow) -> Self {
+        let speed = if rom.supports_cgb() {
+            Some(SpeedSwitch::new())
+        } else {
+            None
+        };
+
         Self {
             ppu: Ppu::new(),
             rom,
@@ -55,79 +77,169 @@ impl<'a> Bus<'a> {
             emu: Emu::new(),
             interrupts: InterruptState::new(),
             timer: Timer::new(),
+            sound: SoundRegisters::new(),
 
             gamepad: Gamepad::new(),
             screen,
+            events: EventRecorder::new(),
+            stopped: false,
+            speed,
 
-            serial_data: [0; 2],
+            serial: Serial::new(),
         }
     }
 
     pub fn cycle(&mut self, cycles: i32) {
         let bus: &mut Bus = make_mut_ref!(self);
         for _ in 0..cycles {
-            for _ in 0..4 {
+            if self.gamepad.take_interrupt() {
+                self.interrupts.enable_flag(Interrupt::Joypad);
+            }
+
+            if self.stopped {
+                self.timer.ticks = self.timer.ticks.wrapping_add(4);
+                for _ in 0..4 {
+                    self.ppu.tick_stopped(bus);
+                }
+                continue;
+            }
+
+            // double speed: the CPU side runs twice as fast, the PPU does not
+            let double_speed = self.speed.as_ref().is_some_and(|speed| speed.double_speed);
+
+            for tick in 0..4 {
                 self.timer.ticks = self.timer.ticks.wrapping_add(1);
                 self.timer.tick(bus);
+                self.serial.tick(self.timer.div, &mut self.interrupts);
+
+                if double_speed && tick % 2 == 1 {
+                    continue;
+                }
+
+                let mode = self.ppu.lcd.lcds & 0b11;
+                let frame = self.ppu.current_frame;
                 self.ppu.tick(bus);
+
+                if self.events.enabled {
+                    self.record_tick_events(mode, frame);
+                } else {
+                    self.interrupts.requested = 0;
+                }
             }
 
             self.dma.tick(bus);
         }
     }
 
+    fn record_tick_events(&mut self, previous_mode: u8, previous_frame: u32) {
+        let ly = self.ppu.lcd.ly;
+        self.events.set_line(ly);
+
+        // LY stays 0 with the LCD off, rotate on its blank frames instead
+        if !self.ppu.lcd.is_lcd_enabled() && self.ppu.current_frame != previous_frame {
+            self.events.end_frame();
+        }
+
+        let mode = self.ppu.lcd.lcds & 0b11;
+        if mode != previous_mode {
+            self.record_event(EventKind::Mode(mode));
+        }
+
+        let requested = std::mem::take(&mut self.interrupts.requested);
+        for bit in 0..5 {
+            if requested & (1 << bit) != 0 {
+                self.record_event(EventKind::Interrupt(1 << bit));
+            }
+        }
+    }
+
+    fn record_event(&mut self, kind: EventKind) {
+        self.events
+            .record(self.ppu.lcd.ly, self.ppu.line_ticks, kind);
+    }
+
     pub fn read(&self, address: u16) -> u8 {
+        // OAM DMA owns the external and video buses, only FF00-FFFF stays reachable
+        if self.dma.is_transfering() && address < 0xFE00 {
+            return self.dma.bus_value();
+        }
+
         match address {
-            interrupts::INTERRUPT_FLAGS_ADDRESS => self.interrupts.flags,
+            // upper 3 bits are unused and read as 1
+            interrupts::INTERRUPT_FLAGS_ADDRESS => self.interrupts.flags | 0xE0,
             // CPU ENABLED REGISTERS
             interrupts::INTERRUPT_ENABLE_ADDRESS => self.interrupts.enabled,
             // ROM DATA
             0..0x8000 => self.rom.read(address),
             // Char/Map DATA
-            0x8000..0xA000 => self.ppu.vram_read(address),
+            0x8000..0xA000 => {
+                if self.ppu.is_vram_locked() {
+                    return 0xFF;
+                }
+                self.ppu.vram_read(address)
+            }
             // Cartridge RAM
             0xA000..0xC000 => self.rom.read(address),
             // WRAM
             0xC000..0xE000 => self.ram.wram_read(address),
-            // ECO RAM
-            0xE000..0xFE00 => {
-                eprintln!("UNSUPPORTED BUS READ {:04X}", address);
-                0
-            }
+            // ECHO RAM
+            0xE000..0xFE00 => self.ram.wram_read(address - 0x2000),
             // OAM
             0xFE00..0xFEA0 => {
-                if self.dma.is_transfering() {
+                if self.dma.is_transfering() || self.ppu.is_oam_locked() {
                     return 0xFF;
                 }
                 self.ppu.oam_read(address)
             }
-            // Reserved unusable
-            0xFEA0..0xFF00 => 0,
-            // IO Registers
+            // Reserved unusable: DMG reads 0x00, or 0xFF while OAM is blocked
+            0xFEA0..0xFF00 => {
+                if self.dma.is_transfering() || self.ppu.is_oam_locked() {
+                    return 0xFF;
+                }
+                0x00
+            }
+            // IO Registers, unused bits and unmapped registers read as 1
             0xFF00..0xFF80 => match address {
                 0xFF00 => self.gamepad.calculate_output(),
-                0xFF01 => self.serial_data[0],
-                0xFF02 => self.serial_data[1],
-                0xFF04..=0xFF07 => self.timer.read(address),
-
+                0xFF01..=0xFF02 => self.serial.read(address),
+                0xFF04..=0xFF06 => self.timer.read(address),
+                0xFF07 => self.timer.read(address) | 0xF8,
+                0xFF10..=0xFF3F => self.sound.read(address),
+                0xFF41 => self.ppu.lcd.read(address) | 0x80,
+                KEY1_ADDRESS => self.speed.as_ref().map_or(0xFF, SpeedSwitch::read),
                 0xFF40..=0xFF4B => self.ppu.lcd.read(address),
-                _ => {
-                    eprintln!("UNSUPPORTED BUS READ {:04X}", address);
-                    0
-                }
+                _ => 0xFF,
             },
             _ => self.ram.hram_read(address),
         }
     }
 
+    /// DMA source read: no PPU locking, E000-FFFF maps to WRAM
+    fn dma_read(&self, address: u16) -> u8 {
+        match address {
+            0..0x8000 | 0xA000..0xC000 => self.rom.read(address),
+            0x8000..0xA000 => self.ppu.vram_read(address),
+            0xC000..0xE000 => self.ram.wram_read(address),
+            _ => self.ram.wram_read(address - 0x2000),
+        }
+    }
+
     pub fn read16(&self, address: u16) -> u16 {
         let lo: u8 = self.read(address);
-        let hi: u8 = self.read(address);
+        let hi: u8 = self.read(address.wrapping_add(1));
 
         bytes_to_word!(lo, hi)
     }
 
     pub fn write(&mut self, address: u16, value: u8) {
+        if (0xFF00..0xFF80).contains(&address) || address == INTERRUPT_ENABLE_ADDRESS {
+            self.record_event(EventKind::IoWrite { address, value });
+        }
+
+        if self.dma.is_transfering() && address < 0xFE00 {
+            return;
+        }
+
         match address {
             // CPU SET ENABLE REGISTER
             interrupts::INTERRUPT_ENABLE_ADDRESS => self.interrupts.enabled = value,
@@ -135,35 +247,49 @@ impl<'a> Bus<'a> {
             // ROM DATA
             ..0x8000 => self.rom.write(address, value),
             // Char/Map DATA
-            0x8000..0xA000 => self.ppu.vram_write(address, value),
+            0x8000..0xA000 => {
+                if self.ppu.is_vram_locked() {
+                    return;
+                }
+                self.ppu.vram_write(address, value);
+            }
             // EXT-RAM
             0xA000..0xC000 => self.rom.write(address, value),
             // WRAM
             0xC000..0xE000 => self.ram.wram_write(address, value),
-            // Reserved echo RAM
-            0xE000..0xFE00 => eprintln!("UNSUPPORTED BUS WRITE {:04X}", address),
+            // ECHO RAM
+            0xE000..0xFE00 => self.ram.wram_write(address - 0x2000, value),
             // OAM
             0xFE00..0xFEA0 => {
-                if self.dma.is_transfering() {
+                if self.dma.is_transfering() || self.ppu.is_oam_locked() {
                     return;
                 }
                 self.ppu.oam_write(address, value);
             }
             // Reserved unusable
-            0xFEA0..0xFF00 => (), //eprintln!("UNSUPPORTED BUS WRITE {:04X}", address),
+            0xFEA0..0xFF00 => (),
             // IO Registers
             0xFF00..0xFF80 => match address {
                 0xFF00 => self.gamepad.set_selector(value),
-                0xFF01 => self.serial_data[0] = value,
-                0xFF02 => self.serial_data[1] = value,
+                0xFF01..=0xFF02 => self.serial.write(address, value),
                 0xFF04..=0xFF07 => self.timer.write(address, value),
+                0xFF10..=0xFF3F => self.sound.write(address, value),
+                KEY1_ADDRESS => {
+                    if let Some(speed) = &mut self.speed {
+                        speed.write(value);
+                    }
+                }
                 0xFF40..=0xFF4B => {
                     if address == 0xFF46 {
+                        self.record_event(EventKind::DmaStart(value));
                         self.dma.start(value);
                     }
+                    if address == 0xFF41 {
+                        self.ppu.stat_write_bug(&mut self.interrupts);
+                    }
                     self.ppu.lcd.write(address, value);
                 }
-                _ => (), // eprintln!("UNSUPPORTED BUS WRITE {:04X} VALUE {:04X}", address, value),
+                _ => (),
             },
             _ => self.ram.hram_write(address, value),
         }
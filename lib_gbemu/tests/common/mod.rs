@@ -0,0 +1,124 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Helpers shared by the integration tests
+//
+
+#![allow(dead_code)]
+
+use lib_gbemu::{
+    cartridge::rom::Rom,
+    cpu::Cpu,
+    gpu::{
+        ppu::{LINES_PER_FRAME, TICKS_PER_LINE},
+        Color, GbWindow,
+    },
+    memory::Bus,
+};
+
+use std::fs;
+
+/// Test code runs from WRAM so it can be written through the bus
+pub const CODE_ADDRESS: u16 = 0xC000;
+/// Entry of the code placed by `code_rom`, right after the header
+pub const ROM_CODE_ADDRESS: u16 = 0x0150;
+
+/// Keeps the last frame drawn
+pub struct FrameCapture {
+    pub frame: Vec<Color>,
+}
+
+impl FrameCapture {
+    pub fn new() -> Self {
+        Self { frame: Vec::new() }
+    }
+}
+
+impl GbWindow for FrameCapture {
+    fn draw_frame(&mut self, buffer: &[Color]) {
+        self.frame = buffer.to_vec();
+    }
+
+    fn present(&mut self) {}
+}
+
+/// Empty 32 KiB ROM-only cartridge with a valid header checksum
+pub fn empty_rom(name: &str) -> Rom {
+    write_rom(name, vec![0u8; 0x8000])
+}
+
+/// Same as `empty_rom`, flagged as CGB compatible
+pub fn cgb_rom(name: &str) -> Rom {
+    let mut data = vec![0u8; 0x8000];
+    data[0x143] = 0x80;
+
+    write_rom(name, data)
+}
+
+/// ROM with `code` at ROM_CODE_ADDRESS and RETI on every interrupt vector
+pub fn code_rom(name: &str, code: &[u8]) -> Rom {
+    let mut data = vec![0u8; 0x8000];
+    for vector in (0x40..=0x60).step_by(8) {
+        data[vector] = 0xD9;
+    }
+
+    let start = ROM_CODE_ADDRESS as usize;
+    data[start..start + code.len()].copy_from_slice(code);
+
+    write_rom(name, data)
+}
+
+/// Points PC at the code of a `code_rom`, interrupts disabled
+pub fn start_rom_code(cpu: &mut Cpu, bus: &mut Bus) {
+    bus.interrupts.enabled = 0;
+    bus.interrupts.flags = 0;
+    cpu.regs.pc = ROM_CODE_ADDRESS;
+    cpu.regs.sp = 0xDFF0;
+}
+
+/// Fixes the header checksum of `data` and loads it from the temp dir
+pub fn write_rom(name: &str, mut data: Vec<u8>) -> Rom {
+    let mut checksum: u8 = 0;
+    for byte in &data[0x134..=0x14C] {
+        checksum = checksum.wrapping_sub(*byte).wrapping_sub(1);
+    }
+    data[0x14D] = checksum;
+
+    let path = std::env::temp_dir().join(format!("gbemu_test_{}.gb", name));
+    fs::write(&path, data).unwrap();
+
+    Rom::load(path.to_string_lossy().into_owned()).unwrap().0
+}
+
+/// Copies `code` to CODE_ADDRESS and points PC at it, interrupts disabled
+pub fn load_code(cpu: &mut Cpu, bus: &mut Bus, code: &[u8]) {
+    for (offset, byte) in code.iter().enumerate() {
+        bus.write(CODE_ADDRESS + offset as u16, *byte);
+    }
+
+    bus.interrupts.enabled = 0;
+    bus.interrupts.flags = 0;
+    cpu.regs.pc = CODE_ADDRESS;
+    cpu.regs.sp = 0xDFF0;
+}
+
+/// M-cycles taken by one `Cpu::step`
+pub fn step_cycles(cpu: &mut Cpu, bus: &mut Bus) -> u32 {
+    let start = bus.timer.ticks;
+    cpu.step(bus);
+
+    bus.timer.ticks.wrapping_sub(start) / 4
+}
+
+/// Runs M-cycles until STAT reports `mode`, at most one frame
+pub fn run_to_mode(bus: &mut Bus, mode: u8) {
+    for _ in 0..LINES_PER_FRAME * TICKS_PER_LINE / 4 {
+        if bus.read(0xFF41) & 0b11 == mode {
+            return;
+        }
+        bus.cycle(1);
+    }
+
+    panic!("PPU never reached mode {}", mode);
+}
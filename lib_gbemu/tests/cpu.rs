@@ -0,0 +1,211 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use common::CODE_ADDRESS;
+
+use lib_gbemu::{cpu::Cpu, emu::CpuLock, gpu::NullWindow, io::input::GamepadState, memory::Bus};
+
+const STOP: [u8; 3] = [0x10, 0x00, 0x00];
+
+fn press_a(bus: &mut Bus) {
+    bus.gamepad.set_state(GamepadState {
+        a: true,
+        ..GamepadState::default()
+    });
+}
+
+#[test]
+fn stop_resets_div_until_a_button_is_pressed() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("stop"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &STOP);
+    bus.timer.div = 0x1234;
+
+    cpu.step(&mut bus);
+
+    assert!(bus.stopped);
+    assert_eq!(cpu.regs.pc, CODE_ADDRESS + 2);
+    assert_eq!(bus.read(0xFF04), 0);
+
+    cpu.step(&mut bus);
+    assert!(bus.stopped);
+
+    press_a(&mut bus);
+    cpu.step(&mut bus);
+    assert!(!bus.stopped);
+}
+
+#[test]
+fn stop_with_button_held_acts_as_halt() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("stop_held_halt"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &STOP);
+    bus.timer.div = 0x1234;
+    press_a(&mut bus);
+
+    cpu.step(&mut bus);
+
+    assert!(!bus.stopped);
+    assert!(cpu.is_halted);
+    assert_eq!(cpu.regs.pc, CODE_ADDRESS + 2);
+    assert!(bus.timer.div > 0x1234);
+}
+
+#[test]
+fn stop_with_button_held_and_interrupt_pending_acts_as_nop() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("stop_held_nop"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &STOP);
+    bus.timer.div = 0x1234;
+    bus.interrupts.enabled = 0x01;
+    bus.interrupts.flags = 0x01;
+    press_a(&mut bus);
+
+    cpu.step(&mut bus);
+
+    assert!(!bus.stopped);
+    assert!(!cpu.is_halted);
+    assert_eq!(cpu.regs.pc, CODE_ADDRESS + 1);
+    assert!(bus.timer.div > 0x1234);
+}
+
+#[test]
+fn stop_switches_speed_when_key1_is_armed() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::cgb_rom("key1"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &STOP);
+
+    assert_eq!(bus.read(0xFF4D), 0x7E);
+    bus.write(0xFF4D, 0x01);
+    assert_eq!(bus.read(0xFF4D), 0x7F);
+
+    cpu.step(&mut bus);
+
+    assert!(!bus.stopped);
+    assert_eq!(cpu.regs.pc, CODE_ADDRESS + 2);
+    assert_eq!(bus.read(0xFF4D), 0xFE);
+    assert_eq!(bus.read(0xFF04), 0);
+}
+
+#[test]
+fn dmg_cartridge_has_no_key1() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("no_key1"), &mut window);
+
+    bus.write(0xFF4D, 0x01);
+
+    assert_eq!(bus.read(0xFF4D), 0xFF);
+    assert!(bus.speed.is_none());
+}
+
+#[test]
+fn dispatch_cancelled_when_push_overwrites_ie() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("dispatch_cancel"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &[0x00]);
+    cpu.regs.sp = 0x0000;
+    cpu.interrupt_master_enabled = true;
+    bus.interrupts.enabled = 0x01;
+    bus.interrupts.flags = 0x01;
+
+    // NOP, then the dispatch writes PC high (0xC0) to IE at FFFF
+    let cycles = common::step_cycles(&mut cpu, &mut bus);
+
+    assert_eq!(cycles, 1 + 5);
+    assert_eq!(cpu.regs.pc, 0x0000);
+    assert_eq!(cpu.regs.sp, 0xFFFE);
+    assert_eq!(bus.interrupts.enabled, 0xC0);
+    assert_eq!(bus.interrupts.flags, 0x01);
+    assert!(!cpu.interrupt_master_enabled);
+}
+
+const FLAG_Z: u8 = 0x80;
+
+// (name, code, F, M-cycles)
+const CYCLE_TABLE: &[(&str, &[u8], u8, u32)] = &[
+    ("RLC B", &[0xCB, 0x00], 0, 2),
+    ("BIT 0,(HL)", &[0xCB, 0x46], 0, 3),
+    ("RES 0,(HL)", &[0xCB, 0x86], 0, 4),
+    ("SET 0,(HL)", &[0xCB, 0xC6], 0, 4),
+    ("RLC (HL)", &[0xCB, 0x06], 0, 4),
+    ("SWAP (HL)", &[0xCB, 0x36], 0, 4),
+    ("PUSH BC", &[0xC5], 0, 4),
+    ("POP BC", &[0xC1], 0, 3),
+    ("CALL a16", &[0xCD, 0x00, 0xC1], 0, 6),
+    ("CALL NZ taken", &[0xC4, 0x00, 0xC1], 0, 6),
+    ("CALL NZ not taken", &[0xC4, 0x00, 0xC1], FLAG_Z, 3),
+    ("CALL Z taken", &[0xCC, 0x00, 0xC1], FLAG_Z, 6),
+    ("RET", &[0xC9], 0, 4),
+    ("RETI", &[0xD9], 0, 4),
+    ("RET NZ taken", &[0xC0], 0, 5),
+    ("RET NZ not taken", &[0xC0], FLAG_Z, 2),
+    ("RET Z taken", &[0xC8], FLAG_Z, 5),
+    ("LD (a16),SP", &[0x08, 0x00, 0xC2], 0, 5),
+];
+
+#[test]
+fn instruction_cycle_counts() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("cycles"), &mut window);
+    let mut cpu = Cpu::new();
+
+    for (name, code, flags, expected) in CYCLE_TABLE.iter().copied() {
+        common::load_code(&mut cpu, &mut bus, code);
+        cpu.regs.f = flags;
+        cpu.regs.h = 0xC1;
+        cpu.regs.l = 0x00;
+
+        let cycles = common::step_cycles(&mut cpu, &mut bus);
+
+        assert_eq!(cycles, expected, "{}", name);
+    }
+}
+
+#[test]
+fn ld_a16_sp_stores_little_endian() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("ld_a16_sp"), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &[0x08, 0x00, 0xC2]);
+    cpu.regs.sp = 0xBEEF;
+
+    cpu.step(&mut bus);
+
+    assert_eq!(bus.read16(0xC200), 0xBEEF);
+}
+
+#[test]
+fn illegal_opcodes_lock_the_cpu() {
+    for opcode in [0xD3u8, 0xFD] {
+        let mut window = NullWindow;
+        let mut bus = Bus::new(common::empty_rom("illegal_opcode"), &mut window);
+        let mut cpu = Cpu::new();
+        common::load_code(&mut cpu, &mut bus, &[opcode, 0x00, 0x00]);
+
+        cpu.step(&mut bus);
+
+        assert!(cpu.is_locked, "{:02X}", opcode);
+        assert_eq!(
+            bus.emu.cpu_lock,
+            Some(CpuLock {
+                pc: CODE_ADDRESS,
+                opcode,
+            })
+        );
+
+        let pc = cpu.regs.pc;
+        for _ in 0..10 {
+            assert_eq!(common::step_cycles(&mut cpu, &mut bus), 1);
+        }
+        assert_eq!(cpu.regs.pc, pc, "{:02X}", opcode);
+    }
+}
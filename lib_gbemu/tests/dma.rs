@@ -0,0 +1,114 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{gpu::NullWindow, memory::Bus};
+
+const OAM_SIZE: u16 = 0xA0;
+
+// LCD off so OAM is never locked, a distinct pattern in each source page
+fn setup(bus: &mut Bus) {
+    bus.write(0xFF40, 0x00);
+
+    for offset in 0..OAM_SIZE {
+        bus.write(0xC000 + offset, pattern(0xC0, offset));
+        bus.write(0xC100 + offset, pattern(0xC1, offset));
+        bus.write(0xDE00 + offset, pattern(0xDE, offset));
+    }
+}
+
+fn pattern(page: u8, offset: u16) -> u8 {
+    page ^ (offset as u8).wrapping_mul(7) ^ 0x5A
+}
+
+fn oam(bus: &Bus, offset: u16) -> u8 {
+    bus.ppu.oam_read(0xFE00 + offset)
+}
+
+#[test]
+fn transfer_starts_two_cycles_after_the_write() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("dma_delay"), &mut window);
+    setup(&mut bus);
+
+    bus.write(0xFF46, 0xC0);
+
+    // requested: picked up at the end of the write's M-cycle
+    bus.cycle(1);
+    assert_eq!(oam(&bus, 0), 0x00);
+    assert_eq!(bus.read(0xC050), pattern(0xC0, 0x50));
+
+    // starting, then the first byte is copied
+    bus.cycle(1);
+    assert_eq!(oam(&bus, 0), pattern(0xC0, 0));
+    assert_eq!(oam(&bus, 1), 0x00);
+
+    bus.cycle(OAM_SIZE as i32 - 1);
+    for offset in 0..OAM_SIZE {
+        assert_eq!(oam(&bus, offset), pattern(0xC0, offset));
+    }
+    assert_eq!(bus.read(0xC050), pattern(0xC0, 0x50));
+}
+
+#[test]
+fn cpu_reads_below_fe00_see_the_dma_bus() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("dma_bus"), &mut window);
+    setup(&mut bus);
+    bus.write(0xFF80, 0x42);
+
+    bus.write(0xFF46, 0xC0);
+    bus.cycle(2 + 4);
+
+    let value = pattern(0xC0, 4);
+    assert_eq!(bus.read(0x0000), value);
+    assert_eq!(bus.read(0x8000), value);
+    assert_eq!(bus.read(0xC050), value);
+    assert_eq!(bus.read(0xE000), value);
+    assert_eq!(bus.read(0xFE00), 0xFF);
+    assert_eq!(bus.read(0xFF80), 0x42);
+}
+
+#[test]
+fn restart_takes_over_after_its_setup() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("dma_restart"), &mut window);
+    setup(&mut bus);
+
+    bus.write(0xFF46, 0xC0);
+    bus.cycle(2 + 10);
+    bus.write(0xFF46, 0xC1);
+
+    // the old transfer keeps running while the new one is set up
+    bus.cycle(1);
+    assert_eq!(oam(&bus, 11), pattern(0xC0, 11));
+    assert_eq!(bus.read(0xC050), pattern(0xC0, 11));
+
+    bus.cycle(1);
+    assert_eq!(oam(&bus, 0), pattern(0xC1, 0));
+    assert_eq!(oam(&bus, 12), 0x00);
+
+    bus.cycle(OAM_SIZE as i32 - 1);
+    for offset in 0..OAM_SIZE {
+        assert_eq!(oam(&bus, offset), pattern(0xC1, offset));
+    }
+}
+
+#[test]
+fn sources_from_e000_map_to_wram() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("dma_echo"), &mut window);
+    setup(&mut bus);
+
+    for (source, page) in [(0xE0, 0xC0), (0xFE, 0xDE)].iter().copied() {
+        bus.write(0xFF46, source);
+        bus.cycle(1 + OAM_SIZE as i32);
+
+        for offset in 0..OAM_SIZE {
+            assert_eq!(oam(&bus, offset), pattern(page, offset), "{:02X}", source);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+use lib_gbemu::gpu::{
+    filter::{dot_matrix, ColorCorrection, FrameBlender, PostProcess},
+    Color, X_RES, Y_RES,
+};
+
+const WHITE: Color = 0xFFFFFFFF;
+const BLACK: Color = 0xFF000000;
+// white with a quarter of black mixed in, the dot-matrix gap color
+const GAP: Color = 0xFFBFBFBF;
+
+#[test]
+fn frame_blender_mixes_in_the_previous_frame() {
+    let mut blender = FrameBlender::new(64);
+
+    let mut first = vec![BLACK, 0xFF804020];
+    blender.apply(&mut first);
+    assert_eq!(first, vec![BLACK, 0xFF804020]);
+
+    // 3/4 of the new frame, 1/4 of the previous one
+    let mut second = vec![WHITE, 0xFF00FF00];
+    blender.apply(&mut second);
+    assert_eq!(second, vec![0xFFBFBFBF, 0xFF20CF08]);
+}
+
+#[test]
+fn color_correction_keeps_white_and_black() {
+    let correction = ColorCorrection::new();
+    let mut frame = vec![WHITE, BLACK];
+
+    correction.apply(&mut frame);
+
+    assert_eq!(frame, vec![WHITE, BLACK]);
+}
+
+#[test]
+fn dot_matrix_darkens_the_last_row_and_column_of_each_dot() {
+    let mut out = Vec::new();
+
+    dot_matrix(&[WHITE, BLACK], 2, 1, 3, &mut out);
+
+    #[rustfmt::skip]
+    let expected = vec![
+        WHITE, WHITE, GAP, BLACK, BLACK, BLACK,
+        WHITE, WHITE, GAP, BLACK, BLACK, BLACK,
+        GAP,   GAP,   GAP, BLACK, BLACK, BLACK,
+    ];
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn dot_matrix_uses_the_post_process_scale() {
+    let mut post_process = PostProcess::new();
+    post_process.dot_matrix = true;
+    post_process.dot_matrix_scale = 4;
+    let frame = vec![WHITE; (X_RES * Y_RES) as usize];
+
+    let (out, width, height) = post_process.apply(&frame);
+
+    assert_eq!((width, height), (X_RES as usize * 4, Y_RES as usize * 4));
+    for y in 0..8 {
+        let row = &out[y * width..y * width + 8];
+        let expected = if y % 4 == 3 {
+            [GAP; 8]
+        } else {
+            [WHITE, WHITE, WHITE, GAP, WHITE, WHITE, WHITE, GAP]
+        };
+        assert_eq!(row, expected, "row {}", y);
+    }
+}
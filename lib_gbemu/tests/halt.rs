@@ -0,0 +1,90 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use common::ROM_CODE_ADDRESS;
+
+use lib_gbemu::{cpu::Cpu, gpu::NullWindow, memory::Bus};
+
+const HALT: u8 = 0x76;
+const INC_A: u8 = 0x3C;
+const EI: u8 = 0xFB;
+const DI: u8 = 0xF3;
+const NOP: u8 = 0x00;
+
+#[test]
+fn halt_bug_runs_the_next_byte_twice() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(
+        common::code_rom("halt_bug", &[HALT, INC_A, NOP]),
+        &mut window,
+    );
+    let mut cpu = Cpu::new();
+    common::start_rom_code(&mut cpu, &mut bus);
+    cpu.regs.a = 0;
+    // IME off with IF & IE already set: HALT does not halt
+    bus.interrupts.enabled = 0x04;
+    bus.interrupts.flags = 0x04;
+
+    cpu.step(&mut bus);
+    assert!(!cpu.is_halted);
+    assert_eq!(cpu.regs.pc, ROM_CODE_ADDRESS + 1);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.regs.pc, ROM_CODE_ADDRESS + 1);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.regs.pc, ROM_CODE_ADDRESS + 2);
+    assert_eq!(cpu.regs.a, 2);
+    assert_eq!(bus.interrupts.flags, 0x04);
+}
+
+#[test]
+fn ei_then_di_services_no_interrupt() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::code_rom("ei_di", &[EI, DI, NOP]), &mut window);
+    let mut cpu = Cpu::new();
+    common::start_rom_code(&mut cpu, &mut bus);
+    bus.interrupts.enabled = 0x04;
+    bus.interrupts.flags = 0x04;
+
+    for _ in 0..3 {
+        cpu.step(&mut bus);
+    }
+
+    assert_eq!(cpu.regs.pc, ROM_CODE_ADDRESS + 3);
+    assert!(!cpu.interrupt_master_enabled);
+    assert_eq!(bus.interrupts.flags, 0x04);
+}
+
+#[test]
+fn ei_halt_returns_into_the_halt() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::code_rom("ei_halt", &[EI, HALT, NOP]), &mut window);
+    let mut cpu = Cpu::new();
+    common::start_rom_code(&mut cpu, &mut bus);
+    bus.interrupts.enabled = 0x04;
+    bus.interrupts.flags = 0x04;
+
+    // EI, then HALT with IME still delayed: the timer interrupt is
+    // dispatched with the HALT itself as return address
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+
+    let halt_address = ROM_CODE_ADDRESS + 1;
+    assert_eq!(cpu.regs.pc, 0x50);
+    assert_eq!(bus.read(cpu.regs.sp), halt_address as u8);
+    assert_eq!(bus.read(cpu.regs.sp + 1), (halt_address >> 8) as u8);
+    assert_eq!(bus.interrupts.flags, 0x00);
+
+    // RETI goes back to HALT, which now halts for real
+    cpu.step(&mut bus);
+    assert_eq!(cpu.regs.pc, halt_address);
+
+    cpu.step(&mut bus);
+    assert!(cpu.is_halted);
+    assert_eq!(cpu.regs.pc, halt_address + 1);
+}
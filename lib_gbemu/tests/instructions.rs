@@ -0,0 +1,199 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Single instructions run from WRAM on a blank cartridge
+//
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{cpu::Cpu, gpu::NullWindow, memory::Bus};
+
+const HL_ADDRESS: u16 = 0xC100;
+
+const FLAG_Z: u8 = 0x80;
+const FLAG_N: u8 = 0x40;
+const FLAG_H: u8 = 0x20;
+const FLAG_C: u8 = 0x10;
+
+/// Runs one instruction with HL pointing at WRAM, `check` gets its M-cycles
+fn run(
+    name: &str,
+    code: &[u8],
+    setup: impl FnOnce(&mut Cpu, &mut Bus),
+    check: impl FnOnce(&Cpu, &Bus, u32),
+) {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom(name), &mut window);
+    let mut cpu = Cpu::new();
+
+    common::load_code(&mut cpu, &mut bus, code);
+    cpu.regs.h = (HL_ADDRESS >> 8) as u8;
+    cpu.regs.l = HL_ADDRESS as u8;
+    setup(&mut cpu, &mut bus);
+
+    let cycles = common::step_cycles(&mut cpu, &mut bus);
+    check(&cpu, &bus, cycles);
+}
+
+// (A, operand, carry in, result, flags)
+const ADC_TABLE: &[(u8, u8, bool, u8, u8)] = &[
+    (0x0E, 0x01, true, 0x10, FLAG_H),
+    (0x0F, 0x00, true, 0x10, FLAG_H),
+    (0xFF, 0x00, true, 0x00, FLAG_Z | FLAG_H | FLAG_C),
+    (0xF0, 0x0F, true, 0x00, FLAG_Z | FLAG_H | FLAG_C),
+    (0xF0, 0x10, false, 0x00, FLAG_Z | FLAG_C),
+    (0x01, 0x01, true, 0x03, 0),
+];
+
+const SBC_TABLE: &[(u8, u8, bool, u8, u8)] = &[
+    (0x10, 0x00, true, 0x0F, FLAG_N | FLAG_H),
+    (0x10, 0x0F, true, 0x00, FLAG_Z | FLAG_N | FLAG_H),
+    (0x00, 0x00, true, 0xFF, FLAG_N | FLAG_H | FLAG_C),
+    (0x00, 0xFF, false, 0x01, FLAG_N | FLAG_H | FLAG_C),
+    (0x05, 0x03, true, 0x01, FLAG_N),
+];
+
+#[test]
+fn adc_flags_with_carry_in() {
+    for (a, operand, carry, result, flags) in ADC_TABLE.iter().copied() {
+        // ADC A,B
+        run(
+            "adc_flags_with_carry_in",
+            &[0x88],
+            |cpu, _| {
+                cpu.regs.a = a;
+                cpu.regs.b = operand;
+                cpu.regs.f = if carry { FLAG_C } else { 0 };
+            },
+            |cpu, _, _| {
+                assert_eq!(cpu.regs.a, result, "{:02X} + {:02X}", a, operand);
+                assert_eq!(cpu.regs.f, flags, "{:02X} + {:02X}", a, operand);
+            },
+        );
+    }
+}
+
+#[test]
+fn sbc_flags_with_carry_in() {
+    for (a, operand, carry, result, flags) in SBC_TABLE.iter().copied() {
+        // SBC A,d8
+        run(
+            "sbc_flags_with_carry_in",
+            &[0xDE, operand],
+            |cpu, _| {
+                cpu.regs.a = a;
+                cpu.regs.f = if carry { FLAG_C } else { 0 };
+            },
+            |cpu, _, _| {
+                assert_eq!(cpu.regs.a, result, "{:02X} - {:02X}", a, operand);
+                assert_eq!(cpu.regs.f, flags, "{:02X} - {:02X}", a, operand);
+            },
+        );
+    }
+}
+
+#[test]
+fn swap_hl() {
+    run(
+        "swap_hl",
+        &[0xCB, 0x36],
+        |cpu, bus| {
+            cpu.regs.f = FLAG_C;
+            bus.write(HL_ADDRESS, 0xF1);
+        },
+        |_, bus, cycles| {
+            assert_eq!(bus.read(HL_ADDRESS), 0x1F);
+            assert_eq!(cycles, 4);
+        },
+    );
+
+    run(
+        "swap_hl",
+        &[0xCB, 0x36],
+        |cpu, bus| {
+            cpu.regs.f = FLAG_C;
+            bus.write(HL_ADDRESS, 0x00);
+        },
+        |cpu, _, _| assert_eq!(cpu.regs.f, FLAG_Z),
+    );
+}
+
+#[test]
+fn bit_hl_keeps_carry_and_memory() {
+    // BIT 7,(HL)
+    run(
+        "bit_hl_keeps_carry_and_memory",
+        &[0xCB, 0x7E],
+        |cpu, bus| {
+            cpu.regs.f = FLAG_C | FLAG_N;
+            bus.write(HL_ADDRESS, 0x7F);
+        },
+        |cpu, bus, cycles| {
+            assert_eq!(cpu.regs.f, FLAG_Z | FLAG_H | FLAG_C);
+            assert_eq!(bus.read(HL_ADDRESS), 0x7F);
+            assert_eq!(cycles, 3);
+        },
+    );
+
+    run(
+        "bit_hl_keeps_carry_and_memory",
+        &[0xCB, 0x7E],
+        |cpu, bus| {
+            cpu.regs.f = 0;
+            bus.write(HL_ADDRESS, 0x80);
+        },
+        |cpu, _, _| assert_eq!(cpu.regs.f, FLAG_H),
+    );
+}
+
+#[test]
+fn res_and_set_hl_keep_flags() {
+    // RES 0,(HL)
+    run(
+        "res_and_set_hl_keep_flags",
+        &[0xCB, 0x86],
+        |cpu, bus| {
+            cpu.regs.f = FLAG_Z | FLAG_C;
+            bus.write(HL_ADDRESS, 0xFF);
+        },
+        |cpu, bus, cycles| {
+            assert_eq!(bus.read(HL_ADDRESS), 0xFE);
+            assert_eq!(cpu.regs.f, FLAG_Z | FLAG_C);
+            assert_eq!(cycles, 4);
+        },
+    );
+
+    // SET 7,(HL)
+    run(
+        "res_and_set_hl_keep_flags",
+        &[0xCB, 0xFE],
+        |cpu, bus| {
+            cpu.regs.f = FLAG_N | FLAG_H;
+            bus.write(HL_ADDRESS, 0x00);
+        },
+        |cpu, bus, cycles| {
+            assert_eq!(bus.read(HL_ADDRESS), 0x80);
+            assert_eq!(cpu.regs.f, FLAG_N | FLAG_H);
+            assert_eq!(cycles, 4);
+        },
+    );
+}
+
+#[test]
+fn jp_hl_jumps_in_one_cycle() {
+    run(
+        "jp_hl_jumps_in_one_cycle",
+        &[0xE9],
+        |cpu, _| {
+            cpu.regs.h = 0x12;
+            cpu.regs.l = 0x34;
+        },
+        |cpu, _, cycles| {
+            assert_eq!(cpu.regs.pc, 0x1234);
+            assert_eq!(cycles, 1);
+        },
+    );
+}
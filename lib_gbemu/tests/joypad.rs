@@ -0,0 +1,92 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{gpu::NullWindow, io::input::GamepadState, memory::Bus};
+
+const JOYPAD_FLAG: u8 = 0x10;
+// P1 values selecting one group, the selected line is low
+const SELECT_DIRECTIONS: u8 = 0x20;
+const SELECT_BUTTONS: u8 = 0x10;
+const SELECT_BOTH: u8 = 0x00;
+
+// joypad interrupt raised since the last call
+fn take_joypad_interrupt(bus: &mut Bus) -> bool {
+    bus.cycle(1);
+    let raised = bus.interrupts.flags & JOYPAD_FLAG != 0;
+    bus.interrupts.flags &= !JOYPAD_FLAG;
+
+    raised
+}
+
+#[test]
+fn interrupt_on_high_to_low_only() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("joypad_edge"), &mut window);
+    bus.write(0xFF00, SELECT_BUTTONS);
+    take_joypad_interrupt(&mut bus);
+
+    bus.gamepad.set_state(GamepadState {
+        a: true,
+        ..GamepadState::default()
+    });
+    assert!(take_joypad_interrupt(&mut bus));
+    assert_eq!(bus.read(0xFF00), 0xC0 | SELECT_BUTTONS | 0x0E);
+
+    bus.gamepad.set_state(GamepadState::default());
+    assert!(!take_joypad_interrupt(&mut bus));
+
+    // a key of the group that is not selected does not touch the lines
+    bus.gamepad.set_state(GamepadState {
+        up: true,
+        ..GamepadState::default()
+    });
+    assert!(!take_joypad_interrupt(&mut bus));
+    assert_eq!(bus.read(0xFF00), 0xC0 | SELECT_BUTTONS | 0x0F);
+}
+
+#[test]
+fn both_groups_are_anded() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("joypad_and"), &mut window);
+    bus.write(0xFF00, SELECT_BOTH);
+
+    // right is P10, B is P11
+    bus.gamepad.set_state(GamepadState {
+        right: true,
+        b: true,
+        ..GamepadState::default()
+    });
+    assert_eq!(bus.read(0xFF00), 0xC0 | SELECT_BOTH | 0x0C);
+
+    bus.write(0xFF00, SELECT_DIRECTIONS);
+    assert_eq!(bus.read(0xFF00), 0xC0 | SELECT_DIRECTIONS | 0x0E);
+
+    bus.write(0xFF00, SELECT_BUTTONS);
+    assert_eq!(bus.read(0xFF00), 0xC0 | SELECT_BUTTONS | 0x0D);
+
+    bus.write(0xFF00, 0x30);
+    assert_eq!(bus.read(0xFF00), 0xFF);
+}
+
+#[test]
+fn reselecting_with_a_button_held_does_not_refire() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("joypad_reselect"), &mut window);
+    bus.write(0xFF00, SELECT_BUTTONS);
+    bus.gamepad.set_state(GamepadState {
+        start: true,
+        ..GamepadState::default()
+    });
+    assert!(take_joypad_interrupt(&mut bus));
+
+    bus.write(0xFF00, SELECT_BUTTONS);
+    assert!(!take_joypad_interrupt(&mut bus));
+
+    // adding the other group keeps P13 low, no new edge
+    bus.write(0xFF00, SELECT_BOTH);
+    assert!(!take_joypad_interrupt(&mut bus));
+}
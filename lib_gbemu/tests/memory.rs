@@ -0,0 +1,63 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{gpu::NullWindow, memory::Bus};
+
+const MODE_HBLANK: u8 = 0;
+const MODE_OAM: u8 = 2;
+
+/// (name, address, bits that always read as 1)
+const FIXED_BITS_TABLE: [(&str, u16, u8); 9] = [
+    ("IF", 0xFF0F, 0xE0),
+    ("TAC", 0xFF07, 0xF8),
+    ("STAT", 0xFF41, 0x80),
+    ("SC", 0xFF02, 0x7E),
+    ("unmapped FF03", 0xFF03, 0xFF),
+    ("unmapped FF08", 0xFF08, 0xFF),
+    ("unmapped FF4C", 0xFF4C, 0xFF),
+    ("KEY1 on DMG", 0xFF4D, 0xFF),
+    ("unmapped FF7F", 0xFF7F, 0xFF),
+];
+
+#[test]
+fn registers_read_their_fixed_bits_as_1() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("fixed_bits"), &mut window);
+
+    for (name, address, fixed) in FIXED_BITS_TABLE.iter().copied() {
+        bus.write(address, 0x00);
+
+        assert_eq!(bus.read(address) & fixed, fixed, "{}", name);
+    }
+}
+
+#[test]
+fn echo_ram_mirrors_wram_both_ways() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("echo_ram"), &mut window);
+
+    bus.write(0xC123, 0x42);
+    assert_eq!(bus.read(0xE123), 0x42);
+
+    bus.write(0xFDFF, 0x24);
+    assert_eq!(bus.read(0xDDFF), 0x24);
+}
+
+#[test]
+fn unusable_area_reads_0_unless_oam_is_locked() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("unusable"), &mut window);
+
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    bus.write(0xFEA0, 0x42);
+    assert_eq!(bus.read(0xFEA0), 0x00);
+    assert_eq!(bus.read(0xFEFF), 0x00);
+
+    common::run_to_mode(&mut bus, MODE_OAM);
+    assert_eq!(bus.read(0xFEA0), 0xFF);
+    assert_eq!(bus.read(0xFEFF), 0xFF);
+}
@@ -0,0 +1,56 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+use lib_gbemu::gpu::{
+    lcd::Lcd,
+    palette::{parse_palette, PalettePreset, Shades},
+};
+
+const GREEN: Shades = [0xFFE0F8D0, 0xFF88C070, 0xFF346856, 0xFF081820];
+
+#[test]
+fn parses_the_text_format() {
+    let text = "; my palette\n#E0F8D0 #88C070\n0x346856,0X081820\n";
+
+    assert_eq!(parse_palette(text), Ok(GREEN));
+}
+
+#[test]
+fn parses_the_json_format() {
+    let json = r##"{ "colors": ["#E0F8D0", "#88C070", "#346856", "#081820"] }"##;
+
+    assert_eq!(parse_palette(json), Ok(GREEN));
+}
+
+#[test]
+fn rejects_malformed_palettes() {
+    let malformed = [
+        ("three colors", "#E0F8D0 #88C070 #346856"),
+        ("five colors", "#E0F8D0 #88C070 #346856 #081820 #FFFFFF"),
+        ("bad hex", "#E0F8D0 #88C070 #34685G #081820"),
+        ("short color", "#E0F8D0 #88C070 #346 #081820"),
+        ("empty", ""),
+    ];
+
+    for (name, data) in malformed.iter().copied() {
+        assert!(parse_palette(data).is_err(), "{}", name);
+    }
+}
+
+#[test]
+fn obj_palette_writes_land_in_the_sprite_palettes() {
+    let mut lcd = Lcd::new();
+    lcd.set_shades(PalettePreset::Grayscale.shades());
+    let bg_colors = lcd.bg_colors;
+
+    // color 0 is transparent for sprites, its bits are ignored
+    lcd.write(0xFF48, 0b0001_1011);
+    lcd.write(0xFF49, 0b1110_0100);
+
+    let [white, light, dark, black] = PalettePreset::Grayscale.shades();
+    assert_eq!(lcd.sp1_colors, [white, dark, light, white]);
+    assert_eq!(lcd.sp2_colors, [white, light, dark, black]);
+    assert_eq!(lcd.bg_colors, bg_colors);
+}
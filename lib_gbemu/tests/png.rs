@@ -0,0 +1,90 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+use lib_gbemu::gpu::{inspector::Image, png};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+// bitwise CRC-32, independent of the encoder's table
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFFFFFFu32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB88320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn be32(data: &[u8]) -> u32 {
+    u32::from_be_bytes([data[0], data[1], data[2], data[3]])
+}
+
+/// (type, data) of every chunk, checking each CRC on the way
+fn chunks(png: &[u8]) -> Vec<([u8; 4], Vec<u8>)> {
+    let mut chunks = Vec::new();
+    let mut rest = &png[SIGNATURE.len()..];
+
+    while !rest.is_empty() {
+        let len = be32(rest) as usize;
+        let kind = [rest[4], rest[5], rest[6], rest[7]];
+        let data = &rest[8..8 + len];
+        let crc = be32(&rest[8 + len..]);
+
+        assert_eq!(crc, crc32(&rest[4..8 + len]), "{:?}", kind);
+        chunks.push((kind, data.to_vec()));
+        rest = &rest[12 + len..];
+    }
+
+    chunks
+}
+
+#[test]
+fn encodes_a_2x2_image() {
+    let image = Image {
+        width: 2,
+        height: 2,
+        pixels: vec![0xFF112233, 0x80445566, 0x00778899, 0xFFAABBCC],
+    };
+
+    let png = png::encode(&image);
+    assert_eq!(png[..8], SIGNATURE);
+
+    let chunks = chunks(&png);
+    let kinds: Vec<&[u8; 4]> = chunks.iter().map(|(kind, _)| kind).collect();
+    assert_eq!(kinds, [b"IHDR", b"IDAT", b"IEND"]);
+
+    // 2x2, 8 bits, RGBA, deflate, no filter, no interlace
+    let header = &chunks[0].1;
+    assert_eq!(header, &[0, 0, 0, 2, 0, 0, 0, 2, 8, 6, 0, 0, 0]);
+
+    // zlib header, one final stored block, Adler-32 of the filtered rows
+    let idat = &chunks[1].1;
+    let raw: Vec<u8> = vec![
+        0, 0x11, 0x22, 0x33, 0xFF, 0x44, 0x55, 0x66, 0x80, //
+        0, 0x77, 0x88, 0x99, 0x00, 0xAA, 0xBB, 0xCC, 0xFF,
+    ];
+    assert_eq!(idat[..2], [0x78, 0x01]);
+    assert_eq!(idat[2..7], [0x01, 18, 0, !18, 0xFF]);
+    assert_eq!(idat[7..7 + raw.len()], raw[..]);
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in &raw {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    assert_eq!(be32(&idat[7 + raw.len()..]), (b << 16) | a);
+    assert_eq!(idat.len(), 7 + raw.len() + 4);
+
+    // IEND has a well known CRC
+    assert!(chunks[2].1.is_empty());
+    assert_eq!(png[png.len() - 4..], [0xAE, 0x42, 0x60, 0x82]);
+}
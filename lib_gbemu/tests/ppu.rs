@@ -0,0 +1,169 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{
+    cpu::Cpu,
+    gpu::{
+        events::EventKind,
+        ppu::{LINES_PER_FRAME, TICKS_PER_LINE},
+        NullWindow,
+    },
+    memory::Bus,
+};
+
+const CYCLES_PER_FRAME: i32 = (LINES_PER_FRAME * TICKS_PER_LINE / 4) as i32;
+
+#[test]
+fn lcd_off_still_counts_frames() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("lcd_off"), &mut window);
+
+    bus.write(0xFF40, 0x00);
+    bus.cycle(1);
+    let start = bus.ppu.current_frame;
+
+    bus.cycle(CYCLES_PER_FRAME * 5);
+
+    assert_eq!(bus.ppu.current_frame, start + 5);
+}
+
+const STAT_FLAG: u8 = 0x02;
+// STAT bit 6: LY=LYC source
+const STAT_LYC_SOURCE: u8 = 0x40;
+
+#[test]
+fn stat_source_already_high_does_not_retrigger() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("stat_retrigger"), &mut window);
+    let ly = bus.read(0xFF44);
+    bus.write(0xFF45, ly);
+    bus.cycle(1);
+
+    bus.interrupts.flags = 0;
+    bus.write(0xFF41, STAT_LYC_SOURCE);
+    bus.cycle(1);
+    assert_eq!(bus.interrupts.flags & STAT_FLAG, STAT_FLAG);
+
+    bus.interrupts.flags = 0;
+    bus.write(0xFF41, STAT_LYC_SOURCE);
+    bus.cycle(1);
+    assert_eq!(bus.interrupts.flags & STAT_FLAG, 0);
+}
+
+#[test]
+fn stat_write_with_lcd_off_raises_nothing() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("stat_lcd_off"), &mut window);
+    bus.write(0xFF40, 0x00);
+    bus.cycle(1);
+
+    bus.interrupts.flags = 0;
+    bus.write(0xFF41, 0x78);
+    bus.cycle(1);
+
+    assert_eq!(bus.interrupts.flags & STAT_FLAG, 0);
+}
+
+#[test]
+fn lcd_off_blank_frames_rotate_events() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("events_lcd_off"), &mut window);
+    bus.events.enabled = true;
+    bus.write(0xFF40, 0x00);
+    bus.cycle(1 + CYCLES_PER_FRAME);
+
+    bus.write(0xFF42, 0x12);
+    bus.cycle(CYCLES_PER_FRAME);
+    let write = EventKind::IoWrite {
+        address: 0xFF42,
+        value: 0x12,
+    };
+    assert!(bus.events.frame.iter().any(|event| event.kind == write));
+
+    bus.cycle(CYCLES_PER_FRAME);
+    assert!(bus.events.frame.is_empty());
+}
+
+const MODE_HBLANK: u8 = 0;
+const MODE_OAM: u8 = 2;
+const MODE_XFER: u8 = 3;
+
+#[test]
+fn vram_is_locked_in_mode_3() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("vram_lock"), &mut window);
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    bus.write(0x8000, 0x42);
+
+    common::run_to_mode(&mut bus, MODE_XFER);
+    assert_eq!(bus.read(0x8000), 0xFF);
+    bus.write(0x8000, 0x99);
+
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    assert_eq!(bus.read(0x8000), 0x42);
+}
+
+#[test]
+fn oam_is_locked_in_modes_2_and_3() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("oam_lock"), &mut window);
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    bus.write(0xFE00, 0x42);
+
+    common::run_to_mode(&mut bus, MODE_OAM);
+    assert_eq!(bus.read(0xFE00), 0xFF);
+
+    common::run_to_mode(&mut bus, MODE_XFER);
+    assert_eq!(bus.read(0xFE00), 0xFF);
+
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    assert_eq!(bus.read(0xFE00), 0x42);
+}
+
+/// OAM after running the 16-bit INC/DEC `opcode` on HL=FE00 at dot 16 of mode 2
+fn oam_after_bug(name: &str, opcode: u8) -> Vec<u8> {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom(name), &mut window);
+    let mut cpu = Cpu::new();
+    common::load_code(&mut cpu, &mut bus, &[opcode]);
+    cpu.regs.h = 0xFE;
+    cpu.regs.l = 0x00;
+
+    for index in 0..0xA0u16 {
+        bus.ppu.oam_write(0xFE00 + index, (index * 7) as u8);
+    }
+
+    common::run_to_mode(&mut bus, MODE_HBLANK);
+    common::run_to_mode(&mut bus, MODE_OAM);
+    while bus.ppu.line_ticks < 16 {
+        bus.cycle(1);
+    }
+    assert_eq!(bus.ppu.line_ticks, 16);
+    cpu.step(&mut bus);
+
+    (0..0xA0u16).map(|index| bus.ppu.oam_read(index)).collect()
+}
+
+#[test]
+fn inc_dec_rr_on_oam_corrupts_the_scanned_row() {
+    // INC HL, DEC HL
+    for (name, opcode) in [("oam_bug_inc", 0x23), ("oam_bug_dec", 0x2B)] {
+        let oam = oam_after_bug(name, opcode);
+
+        // the opcode fetch takes 4 dots, row 5 (dots 20-23) gets hit: its first
+        // word becomes ((a ^ c) & (b ^ c)) ^ c, the rest is copied from row 4
+        assert_eq!(
+            oam[40..48],
+            [0xF8, 0x07, 0xEE, 0xF5, 0xFC, 0x03, 0x0A, 0x11]
+        );
+        for (index, byte) in oam.iter().enumerate() {
+            if !(40..48).contains(&index) {
+                assert_eq!(*byte, (index * 7) as u8, "{}: OAM byte {}", name, index);
+            }
+        }
+    }
+}
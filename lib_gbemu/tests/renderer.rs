@@ -0,0 +1,306 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use common::FrameCapture;
+
+use lib_gbemu::{
+    cartridge::rom::Rom,
+    cpu::Cpu,
+    gpu::{ppu::Renderer, Color, X_RES},
+    memory::Bus,
+};
+
+const FRAMES: u32 = 3;
+
+struct Lcg(u32);
+
+impl Lcg {
+    fn next(&mut self) -> u8 {
+        self.0 = self.0.wrapping_mul(1103515245).wrapping_add(12345);
+        (self.0 >> 16) as u8
+    }
+}
+
+// 32 KiB ROM that jumps to an endless `JR -2` loop
+fn idle_rom(name: &str) -> Rom {
+    let mut data = vec![0u8; 0x8000];
+    data[0x100..0x104].copy_from_slice(&[0x00, 0xC3, 0x50, 0x01]);
+    data[0x150..0x152].copy_from_slice(&[0x18, 0xFE]);
+
+    common::write_rom(name, data)
+}
+
+// static scene without mid-line register writes: scrolled BG, window, 8x16 sprites
+fn setup_scene(bus: &mut Bus) {
+    let mut rng = Lcg(0x1234_5678);
+
+    for byte in bus.ppu.vram.iter_mut() {
+        *byte = rng.next();
+    }
+
+    for address in 0xFE00..0xFEA0 {
+        bus.ppu.oam_write(address, rng.next());
+    }
+
+    bus.ppu.lcd.lcdc = 0b1110_0111;
+    bus.ppu.lcd.scroll_x = 13;
+    bus.ppu.lcd.scroll_y = 37;
+    bus.write(0xFF4A, 40); // WY
+    bus.write(0xFF4B, 57); // WX
+    bus.write(0xFF47, 0xE4);
+    bus.write(0xFF48, 0xD2);
+    bus.write(0xFF49, 0x1B);
+}
+
+fn render(renderer: Renderer) -> Vec<Color> {
+    render_scene(renderer, "scene", setup_scene)
+}
+
+fn render_scene(renderer: Renderer, name: &str, setup: fn(&mut Bus)) -> Vec<Color> {
+    let rom = idle_rom(&format!("renderer_{}_{:?}", name, renderer));
+    let mut capture = FrameCapture::new();
+
+    {
+        let mut cpu = Cpu::new();
+        let mut bus = Bus::new(rom, &mut capture);
+        bus.ppu.renderer = renderer;
+        setup(&mut bus);
+
+        while bus.ppu.current_frame < FRAMES {
+            cpu.step(&mut bus);
+        }
+    }
+
+    capture.frame
+}
+
+#[test]
+fn scanline_renderer_matches_fifo() {
+    let fifo = render(Renderer::Fifo);
+    let scanline = render(Renderer::Scanline);
+
+    assert!(fifo.iter().any(|color| *color != fifo[0]), "frame is blank");
+    assert!(fifo == scanline, "renderers produced different frames");
+}
+
+// blank BG, window made of solid color 3 tiles at WY = 40, WX = 57
+fn setup_window(bus: &mut Bus) {
+    bus.ppu.vram.fill(0);
+    bus.ppu.vram[0x10..0x20].fill(0xFF);
+    bus.ppu.vram[0x1C00..0x2000].fill(1);
+
+    // LCD on, window map 9C00, window on, tiles at 8000, BG on
+    bus.write(0xFF40, 0b1111_0001);
+    bus.write(0xFF47, 0xE4);
+    bus.write(0xFF4A, 40); // WY
+    bus.write(0xFF4B, 57); // WX
+}
+
+#[test]
+fn window_position_from_registers() {
+    for renderer in [Renderer::Fifo, Renderer::Scanline].iter().copied() {
+        let frame = render_scene(renderer, "window", setup_window);
+        let background = frame[0];
+
+        let first = frame
+            .iter()
+            .position(|color| *color != background)
+            .expect("window not drawn");
+        let (x, y) = (first % X_RES as usize, first / X_RES as usize);
+
+        assert_eq!((x, y), (50, 40), "{:?}", renderer);
+    }
+}
+
+fn pixel(frame: &[Color], x: usize, y: usize) -> Color {
+    frame[x + y * X_RES as usize]
+}
+
+// blank BG, 8x8 sprites on with OBP0 = 0xE4, no sprite in OAM
+fn setup_sprites(bus: &mut Bus, lcdc: u8) {
+    bus.ppu.vram.fill(0);
+    for address in 0xFE00..0xFEA0 {
+        bus.ppu.oam_write(address, 0);
+    }
+
+    bus.write(0xFF40, lcdc);
+    bus.write(0xFF47, 0xE4);
+    bus.write(0xFF48, 0xE4);
+}
+
+fn set_sprite(bus: &mut Bus, index: u16, y: u8, x: u8, tile: u8, flags: u8) {
+    for (offset, value) in [y, x, tile, flags].iter().enumerate() {
+        bus.ppu
+            .oam_write(0xFE00 + index * 4 + offset as u16, *value);
+    }
+}
+
+// tile `tile` filled with color index `color` (1..=3)
+fn solid_tile(bus: &mut Bus, tile: usize, color: u8) {
+    for row in 0..8 {
+        set_tile_row(bus, tile, row, color);
+    }
+}
+
+fn set_tile_row(bus: &mut Bus, tile: usize, row: usize, color: u8) {
+    let address = tile * 16 + row * 2;
+    bus.ppu.vram[address] = if color & 1 != 0 { 0xFF } else { 0 };
+    bus.ppu.vram[address + 1] = if color & 2 != 0 { 0xFF } else { 0 };
+}
+
+// line 10: ten sprites at X = 0 first in OAM, then a visible one
+fn setup_sprite_limit(bus: &mut Bus) {
+    setup_sprites(bus, 0b1000_0011);
+    solid_tile(bus, 1, 3);
+
+    for index in 0..10 {
+        set_sprite(bus, index, 16 + 10, 0, 1, 0);
+    }
+    set_sprite(bus, 10, 16 + 10, 8 + 20, 1, 0);
+}
+
+// same with nine hidden sprites, the visible one is the tenth
+fn setup_below_sprite_limit(bus: &mut Bus) {
+    setup_sprite_limit(bus);
+    set_sprite(bus, 9, 0, 0, 1, 0);
+}
+
+#[test]
+fn sprite_limit_counts_sprites_at_x_0() {
+    for renderer in [Renderer::Fifo, Renderer::Scanline].iter().copied() {
+        let frame = render_scene(renderer, "sprite_limit", setup_sprite_limit);
+        assert!(
+            frame.iter().all(|color| *color == frame[0]),
+            "{:?}: 11th sprite drawn",
+            renderer
+        );
+
+        let frame = render_scene(renderer, "sprite_below_limit", setup_below_sprite_limit);
+        assert_ne!(pixel(&frame, 20, 10), frame[0], "{:?}", renderer);
+    }
+}
+
+// tile 1 is color 3, tile 2 color 1
+//   lines 0-7:   OAM 0 at X = 28 (tile 2), OAM 1 at X = 24 (tile 1)
+//   lines 16-23: OAM 2 and 3 both at X = 48, tile 2 then tile 1
+//   lines 32-39: OAM 4 and 5 both at X = 80, tile 1 then tile 2
+fn setup_sprite_priority(bus: &mut Bus) {
+    setup_sprites(bus, 0b1000_0011);
+    solid_tile(bus, 1, 3);
+    solid_tile(bus, 2, 1);
+
+    set_sprite(bus, 0, 16, 28, 2, 0);
+    set_sprite(bus, 1, 16, 24, 1, 0);
+    set_sprite(bus, 2, 32, 48, 2, 0);
+    set_sprite(bus, 3, 32, 48, 1, 0);
+    set_sprite(bus, 4, 48, 80, 1, 0);
+    set_sprite(bus, 5, 48, 80, 2, 0);
+}
+
+#[test]
+fn sprite_priority_smaller_x_then_oam_order() {
+    for renderer in [Renderer::Fifo, Renderer::Scanline].iter().copied() {
+        let frame = render_scene(renderer, "sprite_priority", setup_sprite_priority);
+        let color_1 = pixel(&frame, 24, 0);
+        let color_3 = pixel(&frame, 16, 0);
+        assert_ne!(color_1, color_3, "{:?}", renderer);
+
+        // overlap of X = 24 and X = 28: smaller X wins though later in OAM
+        assert_eq!(pixel(&frame, 20, 0), color_3, "{:?}", renderer);
+        // same X: first in OAM wins
+        assert_eq!(pixel(&frame, 40, 16), color_1, "{:?}", renderer);
+        assert_eq!(pixel(&frame, 72, 32), color_3, "{:?}", renderer);
+    }
+}
+
+// 8x16 sprites using tile 3, drawn from tiles 2 and 3:
+// tile 2 row 0 is color 3, rows 1-7 color 1, tile 3 is color 2.
+// X = 8 is upright, X = 24 flipped vertically
+fn setup_tall_sprites(bus: &mut Bus) {
+    setup_sprites(bus, 0b1000_0111);
+    solid_tile(bus, 2, 1);
+    set_tile_row(bus, 2, 0, 3);
+    solid_tile(bus, 3, 2);
+
+    set_sprite(bus, 0, 16, 8, 3, 0);
+    set_sprite(bus, 1, 16, 24, 3, 0x40);
+}
+
+#[test]
+fn tall_sprites_ignore_tile_bit_0_and_flip_both_halves() {
+    for renderer in [Renderer::Fifo, Renderer::Scanline].iter().copied() {
+        let frame = render_scene(renderer, "sprite_tall", setup_tall_sprites);
+        let background = pixel(&frame, 100, 100);
+
+        let top = pixel(&frame, 0, 0);
+        let middle = pixel(&frame, 0, 1);
+        let bottom = pixel(&frame, 0, 8);
+        assert!(
+            top != middle && middle != bottom && top != bottom,
+            "{:?}: top tile not taken from tile 2",
+            renderer
+        );
+        assert_ne!(bottom, background, "{:?}", renderer);
+        assert_eq!(pixel(&frame, 0, 16), background, "{:?}", renderer);
+
+        for y in 0..16 {
+            assert_eq!(
+                pixel(&frame, 16, 15 - y),
+                pixel(&frame, 0, y),
+                "{:?}: flipped row {}",
+                renderer,
+                y
+            );
+        }
+    }
+}
+
+// solid color 3 BG, BGP switched from 0xE4 to 0x00 at `dot` of line 10
+fn render_bgp_split(dot: u32) -> Vec<Color> {
+    let rom = idle_rom(&format!("renderer_bgp_split_{}", dot));
+    let mut capture = FrameCapture::new();
+
+    {
+        let mut bus = Bus::new(rom, &mut capture);
+        bus.ppu.vram.fill(0);
+        bus.ppu.vram[0..16].fill(0xFF);
+        bus.write(0xFF40, 0b1001_0001);
+        bus.write(0xFF47, 0xE4);
+
+        while bus.ppu.current_frame < 1 {
+            bus.cycle(1);
+        }
+        while bus.ppu.lcd.ly != 10 || bus.ppu.line_ticks < dot {
+            bus.cycle(1);
+        }
+        assert_eq!(bus.ppu.line_ticks, dot);
+
+        bus.write(0xFF47, 0x00);
+        while bus.ppu.current_frame < 2 {
+            bus.cycle(1);
+        }
+    }
+
+    capture.frame
+}
+
+#[test]
+fn bgp_write_in_mode_3_splits_the_line() {
+    // X of the first pixel pushed after the write, this FIFO starts pushing
+    // 20 dots into mode 3 and averages 4 pixels every 5 dots
+    for (dot, split) in [(120, 17), (160, 49), (200, 81)].iter().copied() {
+        let frame = render_bgp_split(dot);
+        let black = pixel(&frame, 0, 9);
+        let white = pixel(&frame, 0, 11);
+        assert_ne!(black, white);
+
+        for x in 0..X_RES as usize {
+            let expected = if x < split { black } else { white };
+            assert_eq!(pixel(&frame, x, 10), expected, "dot {}: X = {}", dot, x);
+        }
+    }
+}
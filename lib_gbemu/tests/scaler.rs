@@ -0,0 +1,165 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+use lib_gbemu::gpu::{scaler::Scaler, Color};
+
+// staircase edge between white and black
+const DIAGONAL: [&str; 4] = ["WWWB", "WWBB", "WBBB", "BBBB"];
+const CHECKER: [&str; 4] = ["WBWB", "BWBW", "WBWB", "BWBW"];
+
+/// W white, B black, l m d light, middle and dark gray
+fn image(rows: &[&str]) -> Vec<Color> {
+    let gray = |level: u32| 0xFF000000 | (level << 16) | (level << 8) | level;
+
+    rows.iter()
+        .flat_map(|row| row.chars())
+        .map(|pixel| match pixel {
+            'W' => gray(0xFF),
+            'l' => gray(0xBF),
+            'm' => gray(0x7F),
+            'd' => gray(0x3F),
+            'B' => gray(0x00),
+            _ => panic!("bad pixel {}", pixel),
+        })
+        .collect()
+}
+
+fn check(scaler: Scaler, input: &[&str], expected: &[&str]) {
+    let mut out = Vec::new();
+    let (width, height) = scaler.apply(&image(input), 4, 4, &mut out);
+
+    assert_eq!((width, height), (expected[0].len(), expected.len()));
+    assert!(out == image(expected), "{:?} on {:?}", scaler, input);
+}
+
+#[test]
+fn factor_and_dimensions() {
+    let factors = [
+        (Scaler::Nearest, 1),
+        (Scaler::Scale2x, 2),
+        (Scaler::Scale3x, 3),
+        (Scaler::Hq2x, 2),
+        (Scaler::Xbr2x, 2),
+    ];
+    let frame = vec![0xFF000000; 5 * 3];
+
+    for (scaler, factor) in factors.iter().copied() {
+        assert_eq!(scaler.factor(), factor, "{:?}", scaler);
+
+        let mut out = Vec::new();
+        let (width, height) = scaler.apply(&frame, 5, 3, &mut out);
+        assert_eq!((width, height), (5 * factor, 3 * factor), "{:?}", scaler);
+        assert_eq!(out.len(), width * height, "{:?}", scaler);
+    }
+}
+
+#[test]
+fn scale2x_golden() {
+    #[rustfmt::skip]
+    check(Scaler::Scale2x, &DIAGONAL, &[
+        "WWWWWWBB",
+        "WWWWWBBB",
+        "WWWWWBBB",
+        "WWWBBBBB",
+        "WWWBBBBB",
+        "WBBBBBBB",
+        "BBBBBBBB",
+        "BBBBBBBB",
+    ]);
+
+    // edges are clamped, so only the border pixels see a neighbour twice
+    #[rustfmt::skip]
+    check(Scaler::Scale2x, &CHECKER, &[
+        "WWBBWWBB",
+        "WBBBWWWB",
+        "BBWWBBWW",
+        "BBWWBBWW",
+        "WWBBWWBB",
+        "WWBBWWBB",
+        "BWWWBBBW",
+        "BBWWBBWW",
+    ]);
+}
+
+#[test]
+fn scale3x_golden() {
+    #[rustfmt::skip]
+    check(Scaler::Scale3x, &DIAGONAL, &[
+        "WWWWWWWWWBBB",
+        "WWWWWWWWBBBB",
+        "WWWWWWWWBBBB",
+        "WWWWWWWBBBBB",
+        "WWWWWWBBBBBB",
+        "WWWWWBBBBBBB",
+        "WWWWBBBBBBBB",
+        "WWWBBBBBBBBB",
+        "WBBBBBBBBBBB",
+        "BBBBBBBBBBBB",
+        "BBBBBBBBBBBB",
+        "BBBBBBBBBBBB",
+    ]);
+
+    #[rustfmt::skip]
+    check(Scaler::Scale3x, &CHECKER, &[
+        "WWWBBBWWWBBB",
+        "WWBBBBWWWWBB",
+        "WBBBBBWWWWWB",
+        "BBBWWWBBBWWW",
+        "BBBWWWBBBWWW",
+        "BBBWWWBBBWWW",
+        "WWWBBBWWWBBB",
+        "WWWBBBWWWBBB",
+        "WWWBBBWWWBBB",
+        "BWWWWWBBBBBW",
+        "BBWWWWBBBBWW",
+        "BBBWWWBBBWWW",
+    ]);
+}
+
+#[test]
+fn xbr_golden() {
+    #[rustfmt::skip]
+    check(Scaler::Xbr2x, &DIAGONAL, &[
+        "WWWWWlBB",
+        "WWWWWdBB",
+        "WWWWmBBB",
+        "WWWmBBBB",
+        "WWmBBBBB",
+        "ldBBBBBB",
+        "BBBBBBBB",
+        "BBBBBBBB",
+    ]);
+
+    // no edge to follow, every pixel stays a 2x2 block
+    #[rustfmt::skip]
+    check(Scaler::Xbr2x, &CHECKER, &[
+        "WWBBWWBB",
+        "WWBBWWBB",
+        "BBWWBBWW",
+        "BBWWBBWW",
+        "WWBBWWBB",
+        "WWBBWWBB",
+        "BBWWBBWW",
+        "BBWWBBWW",
+    ]);
+}
+
+#[test]
+fn hq2x_golden() {
+    #[rustfmt::skip]
+    check(Scaler::Hq2x, &DIAGONAL, &[
+        "WWWWWWBB",
+        "WWWWWmBB",
+        "WWWWmBBB",
+        "WWWmBBBB",
+        "WWmBBBBB",
+        "WmBBBBBB",
+        "BBBBBBBB",
+        "BBBBBBBB",
+    ]);
+
+    let flat = ["WWWW"; 4];
+    check(Scaler::Hq2x, &flat, &["WWWWWWWW"; 8]);
+}
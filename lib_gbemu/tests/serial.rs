@@ -0,0 +1,136 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{
+    gpu::NullWindow,
+    io::serial::{LogLink, SerialLink},
+    memory::Bus,
+};
+
+use std::{cell::RefCell, rc::Rc};
+
+const SERIAL_FLAG: u8 = 0x08;
+// one bit every 512 T-cycles
+const CYCLES_PER_BIT: i32 = 128;
+
+/// Records what it receives, answers with `reply`; as the clock master it
+/// sends `reply` after `delay` T-cycles
+struct Loopback {
+    received: Rc<RefCell<Vec<u8>>>,
+    reply: u8,
+    delay: u32,
+}
+
+impl SerialLink for Loopback {
+    fn transfer(&mut self, value: u8) -> u8 {
+        self.received.borrow_mut().push(value);
+        self.reply
+    }
+
+    fn clock_in(&mut self, value: u8) -> Option<u8> {
+        if self.delay > 0 {
+            self.delay -= 1;
+            return None;
+        }
+
+        self.delay = u32::MAX;
+        self.received.borrow_mut().push(value);
+        Some(self.reply)
+    }
+}
+
+fn connect(bus: &mut Bus, reply: u8, delay: u32) -> Rc<RefCell<Vec<u8>>> {
+    let received = Rc::new(RefCell::new(Vec::new()));
+    bus.serial.link = Box::new(Loopback {
+        received: Rc::clone(&received),
+        reply,
+        delay,
+    });
+
+    received
+}
+
+#[test]
+fn internal_clock_shifts_on_div_bit_8() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("serial_internal"), &mut window);
+    let received = connect(&mut bus, 0xC3, 0);
+
+    // DIV bit 8 low, the first falling edge comes 128 M-cycles after the reset
+    bus.write(0xFF04, 0x00);
+    bus.cycle(1);
+    bus.interrupts.flags = 0;
+
+    bus.write(0xFF01, 0x5A);
+    bus.write(0xFF02, 0x81);
+    assert!(received.borrow().is_empty());
+
+    bus.cycle(CYCLES_PER_BIT - 2);
+    assert_eq!(bus.read(0xFF01), 0x5A);
+
+    bus.cycle(1);
+    assert_eq!(bus.read(0xFF01), 0xB5);
+
+    bus.cycle(CYCLES_PER_BIT * 7 - 1);
+    assert_eq!(bus.read(0xFF02) & 0x80, 0x80);
+    assert_eq!(bus.interrupts.flags & SERIAL_FLAG, 0);
+    assert!(received.borrow().is_empty());
+
+    bus.cycle(1);
+    assert_eq!(*received.borrow(), vec![0x5A]);
+    assert_eq!(bus.read(0xFF01), 0xC3);
+    assert_eq!(bus.read(0xFF02) & 0x80, 0);
+    assert_eq!(bus.interrupts.flags & SERIAL_FLAG, SERIAL_FLAG);
+}
+
+#[test]
+fn external_clock_waits_for_the_peer() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("serial_external"), &mut window);
+    let received = connect(&mut bus, 0x3C, 40);
+    bus.interrupts.flags = 0;
+
+    bus.write(0xFF01, 0x99);
+    bus.write(0xFF02, 0x80);
+
+    bus.cycle(10);
+    assert_eq!(bus.read(0xFF02) & 0x80, 0x80);
+    assert!(received.borrow().is_empty());
+
+    bus.cycle(1);
+    assert_eq!(*received.borrow(), vec![0x99]);
+    assert_eq!(bus.read(0xFF01), 0x3C);
+    assert_eq!(bus.read(0xFF02) & 0x80, 0);
+    assert_eq!(bus.interrupts.flags & SERIAL_FLAG, SERIAL_FLAG);
+}
+
+#[test]
+fn external_clock_without_a_peer_never_ends() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("serial_no_link"), &mut window);
+
+    bus.write(0xFF01, 0x99);
+    bus.write(0xFF02, 0x80);
+    bus.cycle(CYCLES_PER_BIT * 16);
+
+    assert_eq!(bus.read(0xFF02) & 0x80, 0x80);
+    assert_eq!(bus.read(0xFF01), 0x99);
+}
+
+#[test]
+fn log_link_writes_whole_lines() {
+    let mut link = LogLink::new(Vec::new());
+
+    for byte in b"ok" {
+        assert_eq!(link.transfer(*byte), 0xFF);
+    }
+    assert!(link.writer.is_empty());
+
+    link.transfer(b'\n');
+    assert_eq!(link.writer, b"SERIAL: ok\n");
+    assert!(link.output.is_empty());
+}
@@ -0,0 +1,461 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+//
+// Acceptance test ROMs run through gbemu-headless. The ROMs are not part of
+// the repository, GB_TEST_ROMS points at a directory holding
+//
+//     dmg-acid2/dmg-acid2.gb
+//     dmg-acid2/reference-dmg.png
+//     blargg/instr_timing/instr_timing.gb
+//     blargg/mem_timing/mem_timing.gb
+//     mooneye/acceptance/interrupts/ie_push.gb
+//     mooneye/acceptance/timer/*.gb
+//
+// These tests are ignored by default, run them with
+//
+//     GB_TEST_ROMS=/path/to/roms cargo test --release --test test_roms -- --ignored
+//
+
+extern crate lib_gbemu;
+
+use lib_gbemu::gpu::Color;
+
+use std::{env, fs, path::PathBuf, process::Command};
+
+fn rom_path(name: &str) -> PathBuf {
+    let dir = env::var_os("GB_TEST_ROMS").expect("GB_TEST_ROMS is not set");
+    let path = PathBuf::from(dir).join(name);
+    assert!(path.exists(), "{} not found", path.display());
+
+    path
+}
+
+/// Runs gbemu-headless and returns its stdout
+fn headless(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_gbemu-headless"))
+        .args(args)
+        .output()
+        .expect("cannot run gbemu-headless");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+
+    assert!(
+        output.status.success(),
+        "gbemu-headless {:?} failed: {}{}",
+        args,
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    stdout
+}
+
+#[test]
+#[ignore]
+fn dmg_acid2() {
+    let rom = rom_path("dmg-acid2/dmg-acid2.gb");
+    let reference = png_decode(&fs::read(rom_path("dmg-acid2/reference-dmg.png")).unwrap());
+    let screenshot = env::temp_dir().join("gbemu_test_dmg_acid2.png");
+
+    headless(&[
+        "--frames",
+        "60",
+        "--screenshot",
+        &screenshot.to_string_lossy(),
+        &rom.to_string_lossy(),
+    ]);
+    let frame = png_decode(&fs::read(&screenshot).unwrap());
+
+    assert_eq!((frame.0, frame.1), (reference.0, reference.1));
+    let wrong = frame
+        .2
+        .iter()
+        .zip(reference.2.iter())
+        .position(|(pixel, expected)| pixel != expected);
+    if let Some(index) = wrong {
+        panic!(
+            "first wrong pixel at ({}, {})",
+            index % frame.0,
+            index / frame.0
+        );
+    }
+}
+
+/// Blargg's ROMs print their result over serial
+fn blargg(name: &str) {
+    let rom = rom_path(name);
+    let output = headless(&["--frames", "600", "--serial-log", &rom.to_string_lossy()]);
+
+    assert!(
+        output.contains("Passed") && !output.contains("Failed"),
+        "{}: {}",
+        name,
+        output
+    );
+}
+
+#[test]
+#[ignore]
+fn blargg_instr_timing() {
+    blargg("blargg/instr_timing/instr_timing.gb");
+}
+
+#[test]
+#[ignore]
+fn blargg_mem_timing() {
+    blargg("blargg/mem_timing/mem_timing.gb");
+}
+
+// Fibonacci numbers in B, C, D, E, H, L mean passed
+const MOONEYE_PASSED: [(&str, u8); 6] = [
+    ("B", 3),
+    ("C", 5),
+    ("D", 8),
+    ("E", 13),
+    ("H", 21),
+    ("L", 34),
+];
+
+/// Mooneye's ROMs report their result in the registers
+fn mooneye(name: &str) {
+    let rom = rom_path(name);
+    let output = headless(&["--frames", "300", "--registers", &rom.to_string_lossy()]);
+
+    for (register, value) in MOONEYE_PASSED.iter() {
+        let expected = format!("{}={:02X}", register, value);
+        assert!(
+            output.split_whitespace().any(|field| field == expected),
+            "{}: {}",
+            name,
+            output
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn mooneye_ie_push() {
+    mooneye("mooneye/acceptance/interrupts/ie_push.gb");
+}
+
+#[test]
+#[ignore]
+fn mooneye_timer() {
+    let roms = [
+        "div_write",
+        "rapid_toggle",
+        "tim00",
+        "tim00_div_trigger",
+        "tim01",
+        "tim01_div_trigger",
+        "tim10",
+        "tim10_div_trigger",
+        "tim11",
+        "tim11_div_trigger",
+        "tima_reload",
+        "tima_write_reloading",
+        "tma_write_reloading",
+    ];
+
+    for rom in roms.iter() {
+        mooneye(&format!("mooneye/acceptance/timer/{}.gb", rom));
+    }
+}
+
+//
+// Just enough of a PNG decoder to read the reference images: inflate,
+// scanline filters, 1-16 bit grayscale, RGB, palette and alpha images
+//
+
+/// Width, height and the pixels as opaque (or alpha) ARGB
+fn png_decode(data: &[u8]) -> (usize, usize, Vec<Color>) {
+    assert_eq!(data[..8], [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+
+    let be32 = |bytes: &[u8]| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let mut header = [0u8; 13];
+    let mut palette = Vec::new();
+    let mut idat = Vec::new();
+
+    let mut pos = 8;
+    while pos < data.len() {
+        let length = be32(&data[pos..]) as usize;
+        let body = &data[pos + 8..pos + 8 + length];
+        match &data[pos + 4..pos + 8] {
+            b"IHDR" => header.copy_from_slice(body),
+            b"PLTE" => palette = body.to_vec(),
+            b"IDAT" => idat.extend_from_slice(body),
+            _ => (),
+        }
+        pos += length + 12;
+    }
+
+    let (width, height) = (be32(&header[0..]) as usize, be32(&header[4..]) as usize);
+    let (depth, color_type) = (header[8] as usize, header[9]);
+    assert_eq!(header[12], 0, "interlaced PNG");
+
+    let channels = match color_type {
+        0 | 3 => 1,
+        2 => 3,
+        4 => 2,
+        6 => 4,
+        _ => panic!("bad PNG color type {}", color_type),
+    };
+    let stride = (width * channels * depth).div_ceil(8);
+    let bpp = (channels * depth / 8).max(1);
+
+    // zlib: 2 byte header, then deflate
+    let raw = inflate(&idat[2..]);
+    let mut rows = vec![0u8; stride * height];
+    for y in 0..height {
+        let filter = raw[y * (stride + 1)];
+        let line = &raw[y * (stride + 1) + 1..(y + 1) * (stride + 1)];
+        for x in 0..stride {
+            let a = if x >= bpp {
+                rows[y * stride + x - bpp]
+            } else {
+                0
+            };
+            let b = if y > 0 { rows[(y - 1) * stride + x] } else { 0 };
+            let c = if x >= bpp && y > 0 {
+                rows[(y - 1) * stride + x - bpp]
+            } else {
+                0
+            };
+            let predicted = match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((a as u16 + b as u16) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => panic!("bad PNG filter {}", filter),
+            };
+            rows[y * stride + x] = line[x].wrapping_add(predicted);
+        }
+    }
+
+    let max = (1u32 << depth.min(8)) - 1;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let row = &rows[y * stride..(y + 1) * stride];
+        let sample = |index: usize| -> u32 {
+            match depth {
+                16 => row[index * 2] as u32,
+                8 => row[index] as u32,
+                _ => {
+                    let bit = index * depth;
+                    (row[bit / 8] as u32 >> (8 - depth - bit % 8)) & max
+                }
+            }
+        };
+        let scale = |value: u32| value * 255 / max;
+
+        for x in 0..width {
+            let i = x * channels;
+            let (a, r, g, b) = match color_type {
+                0 => (255, scale(sample(i)), scale(sample(i)), scale(sample(i))),
+                2 => (255, sample(i), sample(i + 1), sample(i + 2)),
+                3 => {
+                    let entry = &palette[sample(i) as usize * 3..];
+                    (255, entry[0] as u32, entry[1] as u32, entry[2] as u32)
+                }
+                4 => (sample(i + 1), sample(i), sample(i), sample(i)),
+                _ => (sample(i + 3), sample(i), sample(i + 1), sample(i + 2)),
+            };
+            pixels.push((a << 24) | (r << 16) | (g << 8) | b);
+        }
+    }
+
+    (width, height, pixels)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = (
+        (p - a as i16).abs(),
+        (p - b as i16).abs(),
+        (p - c as i16).abs(),
+    );
+
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+const LENGTH_BASE: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+// order the code length code lengths are stored in
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// LSB-first bit reader over a deflate stream
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn read(&mut self, count: u32) -> u32 {
+        let mut value = 0;
+        for i in 0..count {
+            let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+            value |= (bit as u32) << i;
+            self.pos += 1;
+        }
+
+        value
+    }
+
+    fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+}
+
+/// Canonical Huffman code: number of codes per length, symbols in code order
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+        for length in lengths {
+            counts[*length as usize] += 1;
+        }
+        counts[0] = 0;
+
+        let mut symbols = Vec::new();
+        for length in 1..16 {
+            for (symbol, _) in lengths.iter().enumerate().filter(|(_, l)| **l == length) {
+                symbols.push(symbol as u16);
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut Bits) -> usize {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for length in 1..16 {
+            code |= bits.read(1) as i32;
+            let count = self.counts[length] as i32;
+            if code - first < count {
+                return self.symbols[(index + code - first) as usize] as usize;
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        panic!("bad Huffman code");
+    }
+}
+
+fn inflate(data: &[u8]) -> Vec<u8> {
+    let mut bits = Bits { data, pos: 0 };
+    let mut out = Vec::new();
+
+    loop {
+        let last = bits.read(1) == 1;
+        match bits.read(2) {
+            0 => {
+                bits.align();
+                let start = bits.pos / 8;
+                let length = data[start] as usize | (data[start + 1] as usize) << 8;
+                out.extend_from_slice(&data[start + 4..start + 4 + length]);
+                bits.pos = (start + 4 + length) * 8;
+            }
+            1 => {
+                let mut lengths = [8u8; 288];
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                inflate_block(
+                    &mut bits,
+                    &mut out,
+                    &Huffman::new(&lengths),
+                    &Huffman::new(&[5; 30]),
+                );
+            }
+            2 => {
+                let (literals, distances) = read_dynamic_codes(&mut bits);
+                inflate_block(&mut bits, &mut out, &literals, &distances);
+            }
+            _ => panic!("bad deflate block type"),
+        }
+
+        if last {
+            return out;
+        }
+    }
+}
+
+fn read_dynamic_codes(bits: &mut Bits) -> (Huffman, Huffman) {
+    let literal_count = bits.read(5) as usize + 257;
+    let distance_count = bits.read(5) as usize + 1;
+    let code_count = bits.read(4) as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for index in CODE_LENGTH_ORDER.iter().take(code_count) {
+        code_lengths[*index] = bits.read(3) as u8;
+    }
+    let code_lengths = Huffman::new(&code_lengths);
+
+    let mut lengths = Vec::with_capacity(literal_count + distance_count);
+    while lengths.len() < literal_count + distance_count {
+        let (value, repeat) = match code_lengths.decode(bits) {
+            symbol @ 0..=15 => (symbol as u8, 1),
+            16 => (*lengths.last().unwrap(), 3 + bits.read(2)),
+            17 => (0, 3 + bits.read(3)),
+            _ => (0, 11 + bits.read(7)),
+        };
+        for _ in 0..repeat {
+            lengths.push(value);
+        }
+    }
+
+    (
+        Huffman::new(&lengths[..literal_count]),
+        Huffman::new(&lengths[literal_count..]),
+    )
+}
+
+fn inflate_block(bits: &mut Bits, out: &mut Vec<u8>, literals: &Huffman, distances: &Huffman) {
+    loop {
+        let symbol = literals.decode(bits);
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+        if symbol == 256 {
+            return;
+        }
+
+        let symbol = symbol - 257;
+        let length = LENGTH_BASE[symbol] + bits.read(LENGTH_EXTRA[symbol]) as usize;
+        let symbol = distances.decode(bits);
+        let distance = DISTANCE_BASE[symbol] + bits.read(DISTANCE_EXTRA[symbol]) as usize;
+
+        let start = out.len() - distance;
+        for i in 0..length {
+            out.push(out[start + i]);
+        }
+    }
+}
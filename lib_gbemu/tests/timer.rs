@@ -0,0 +1,115 @@
+// Copyright 2026 Maslyna AKA Mykhailo Ordyntsev
+// SPDX-License-Identifier: gpl-3.0-only
+
+extern crate lib_gbemu;
+
+mod common;
+
+use lib_gbemu::{gpu::NullWindow, memory::Bus};
+
+const TIMER_FLAG: u8 = 0x04;
+
+// TAC = 0x05: enabled, DIV bit 3, TIMA ticks every 4 M-cycles
+fn start_timer(bus: &mut Bus, tima: u8, tma: u8) {
+    bus.write(0xFF07, 0x00);
+    bus.write(0xFF04, 0x00);
+    bus.write(0xFF06, tma);
+    bus.write(0xFF05, tima);
+    bus.write(0xFF07, 0x05);
+    bus.interrupts.flags = 0;
+}
+
+#[test]
+fn overflow_reloads_tma_one_cycle_later() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_reload"), &mut window);
+    start_timer(&mut bus, 0xFF, 0x23);
+
+    bus.cycle(4);
+    assert_eq!(bus.read(0xFF05), 0x00);
+    assert_eq!(bus.interrupts.flags & TIMER_FLAG, 0);
+
+    bus.cycle(1);
+    assert_eq!(bus.read(0xFF05), 0x23);
+    assert_eq!(bus.interrupts.flags & TIMER_FLAG, TIMER_FLAG);
+}
+
+#[test]
+fn tima_write_in_delay_cancels_reload() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_cancel"), &mut window);
+    start_timer(&mut bus, 0xFF, 0x23);
+
+    bus.cycle(4);
+    bus.write(0xFF05, 0x42);
+    bus.cycle(1);
+
+    assert_eq!(bus.read(0xFF05), 0x42);
+    assert_eq!(bus.interrupts.flags & TIMER_FLAG, 0);
+}
+
+#[test]
+fn tima_write_while_reloading_is_ignored() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_ignored"), &mut window);
+    start_timer(&mut bus, 0xFF, 0x23);
+
+    bus.cycle(5);
+    bus.write(0xFF05, 0x42);
+    assert_eq!(bus.read(0xFF05), 0x23);
+
+    bus.cycle(1);
+    bus.write(0xFF05, 0x42);
+    assert_eq!(bus.read(0xFF05), 0x42);
+}
+
+#[test]
+fn tma_write_while_reloading_goes_to_tima() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_tma"), &mut window);
+    start_timer(&mut bus, 0xFF, 0x23);
+
+    bus.cycle(5);
+    bus.write(0xFF06, 0x77);
+    assert_eq!(bus.read(0xFF05), 0x77);
+
+    // afterwards TMA only matters on the next overflow
+    bus.cycle(1);
+    bus.write(0xFF06, 0x11);
+    assert_eq!(bus.read(0xFF05), 0x77);
+}
+
+#[test]
+fn div_write_with_selected_bit_high_ticks_tima() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_div_glitch"), &mut window);
+    start_timer(&mut bus, 0x00, 0x00);
+
+    // DIV bit 3 is high after 8 T-cycles
+    bus.cycle(2);
+    bus.write(0xFF04, 0x00);
+    assert_eq!(bus.read(0xFF05), 0x01);
+
+    // bit 3 low: a reset does nothing
+    bus.cycle(1);
+    bus.write(0xFF04, 0x00);
+    assert_eq!(bus.read(0xFF05), 0x01);
+}
+
+#[test]
+fn tac_write_dropping_the_signal_ticks_tima() {
+    let mut window = NullWindow;
+    let mut bus = Bus::new(common::empty_rom("timer_tac_glitch"), &mut window);
+    start_timer(&mut bus, 0x00, 0x00);
+
+    // disable with bit 3 high
+    bus.cycle(2);
+    bus.write(0xFF07, 0x01);
+    assert_eq!(bus.read(0xFF05), 0x01);
+
+    // switch from bit 3 (high) to bit 5 (low)
+    start_timer(&mut bus, 0x00, 0x00);
+    bus.cycle(2);
+    bus.write(0xFF07, 0x06);
+    assert_eq!(bus.read(0xFF05), 0x01);
+}